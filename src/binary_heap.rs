@@ -0,0 +1,324 @@
+use crate::sort::std_quicksort;
+use crate::{check_comparable, InvalidOrderError, OrderOperation, OrderResult};
+use core::cmp::Ordering;
+use core::fmt::{Debug, Error, Formatter};
+use std::vec::Vec;
+
+/// Priority queue for [`PartialOrd`], same as [`BinaryHeap`](`std::collections::BinaryHeap`) but returning
+/// [`InvalidOrderError`] when the elements cannot be compared.
+///
+/// The greatest element by the comparator is at the top. [`new`](`TryBinaryHeap::new`) makes a max-heap,
+/// [`new_min`](`TryBinaryHeap::new_min`) a min-heap, and [`with_comparator`](`TryBinaryHeap::with_comparator`)
+/// takes the comparator returning [`Option<Ordering>`].
+/// An element which the comparator cannot compare with itself, like [`f64::NAN`] for the default ones, is rejected even by an empty heap.
+/// The heap is left unchanged when it returns error, except for [`try_peek_mut`](`TryBinaryHeap::try_peek_mut`).
+/// ```
+/// use try_partialord::*;
+///
+/// let mut heap = TryBinaryHeap::new_min();
+/// assert!(heap.try_push(2.0).is_ok());
+/// assert!(heap.try_push(1.0).is_ok());
+/// assert!(heap.try_push(f64::NAN).is_err());
+/// assert_eq!(heap.peek(), Some(&1.0));
+/// assert_eq!(heap.try_pop(), Ok(Some(1.0)));
+///
+/// let mut heap = TryBinaryHeap::with_comparator(|a: &(u32, f64), b: &(u32, f64)| a.1.partial_cmp(&b.1));
+/// heap.try_push((1, 0.5)).unwrap();
+/// heap.try_push((2, 1.5)).unwrap();
+/// assert_eq!(heap.try_into_sorted_vec(), Ok(vec![(1, 0.5), (2, 1.5)]));
+/// ```
+#[derive(Clone)]
+pub struct TryBinaryHeap<T, C = fn(&T, &T) -> Option<Ordering>> {
+    data: Vec<T>,
+    compare: C,
+}
+
+impl<T: PartialOrd<T>> TryBinaryHeap<T> {
+    /// Creates the empty max-heap.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_comparator(T::partial_cmp)
+    }
+
+    /// Creates the empty min-heap.
+    #[inline]
+    pub fn new_min() -> Self {
+        Self::with_comparator(|a: &T, b: &T| b.partial_cmp(a))
+    }
+
+    /// Builds the max-heap from `v` in linear time.
+    #[inline]
+    pub fn try_from_vec(v: Vec<T>) -> OrderResult<Self> {
+        Self::try_from_vec_with_comparator(v, T::partial_cmp)
+    }
+
+    /// Builds the min-heap from `v` in linear time.
+    #[inline]
+    pub fn try_from_vec_min(v: Vec<T>) -> OrderResult<Self> {
+        Self::try_from_vec_with_comparator(v, |a: &T, b: &T| b.partial_cmp(a))
+    }
+}
+
+impl<T: PartialOrd<T>> Default for TryBinaryHeap<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, C> TryBinaryHeap<T, C>
+where
+    C: FnMut(&T, &T) -> Option<Ordering>,
+{
+    /// Creates the empty heap with the greatest element by `compare` at the top.
+    #[inline]
+    pub fn with_comparator(compare: C) -> Self {
+        TryBinaryHeap {
+            data: Vec::new(),
+            compare,
+        }
+    }
+
+    /// Builds the heap from `v` in linear time, with the greatest element by `compare` at the top.
+    pub fn try_from_vec_with_comparator(v: Vec<T>, compare: C) -> OrderResult<Self> {
+        let mut heap = TryBinaryHeap { data: v, compare };
+        if let [x] = &heap.data[..] {
+            check_comparable(
+                x,
+                &mut heap.compare,
+                InvalidOrderError::with_index(OrderOperation::Heap, 0),
+            )?;
+        }
+        let compare = &mut heap.compare;
+        std_quicksort::heapify(&mut heap.data, &mut |a: &T, b: &T| is_less(compare, a, b))?;
+        Ok(heap)
+    }
+
+    /// Returns the greatest element, or [`None`] if empty.
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Changes the greatest element with `f` and moves it to the right position, or returns [`None`] if empty.
+    ///
+    /// If the changed element cannot be compared, it is removed from the heap and the error is returned.
+    pub fn try_peek_mut<R, F>(&mut self, f: F) -> OrderResult<Option<R>>
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        let r = f(&mut self.data[0]);
+        let sifted = check_comparable(&self.data[0], &mut self.compare, heap_error())
+            .and_then(|_| self.sift_down(0));
+        if let Err(e) = sifted {
+            let last = self.data.len() - 1;
+            self.data.swap(0, last);
+            self.data.pop();
+            self.sift_down(0)?;
+            return Err(e);
+        }
+        Ok(Some(r))
+    }
+
+    /// Adds `x` to the heap.
+    pub fn try_push(&mut self, x: T) -> OrderResult<()> {
+        check_comparable(&x, &mut self.compare, heap_error())?;
+        self.data.push(x);
+        let pos = self.data.len() - 1;
+        if let Err(e) = self.sift_up(pos) {
+            self.data.pop();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the greatest element, or [`None`] if empty.
+    pub fn try_pop(&mut self) -> OrderResult<Option<T>> {
+        let last = match self.data.len() {
+            0 => return Ok(None),
+            len => len - 1,
+        };
+        self.data.swap(0, last);
+        if let Err(e) = self.sift_down_within(0, last) {
+            self.data.swap(0, last);
+            return Err(e);
+        }
+        Ok(self.data.pop())
+    }
+
+    /// Returns the elements sorted in ascending order by the comparator, which is from the bottom to the top.
+    pub fn try_into_sorted_vec(self) -> OrderResult<Vec<T>> {
+        let TryBinaryHeap {
+            mut data,
+            mut compare,
+        } = self;
+        let mut is_less = |a: &T, b: &T| is_less(&mut compare, a, b);
+        // Pop maximal elements from the heap, the same as the second half of heapsort.
+        for i in (1..data.len()).rev() {
+            data.swap(0, i);
+            std_quicksort::sift_down(&mut data[..i], 0, &mut is_less)?;
+        }
+        Ok(data)
+    }
+
+    /// Moves the element at `pos` up to the right position.
+    ///
+    /// All comparisons are done before moving, so the heap is unchanged on error.
+    fn sift_up(&mut self, pos: usize) -> OrderResult<()> {
+        let mut target = pos;
+        while target > 0 {
+            let parent = (target - 1) / 2;
+            if !is_less(&mut self.compare, &self.data[parent], &self.data[pos])? {
+                break;
+            }
+            target = parent;
+        }
+        let mut pos = pos;
+        while pos > target {
+            let parent = (pos - 1) / 2;
+            self.data.swap(pos, parent);
+            pos = parent;
+        }
+        Ok(())
+    }
+
+    fn sift_down(&mut self, node: usize) -> OrderResult<()> {
+        self.sift_down_within(node, self.data.len())
+    }
+
+    /// Moves the element at `node` down to the right position in the heap of the first `len` elements.
+    ///
+    /// Same as [`std_quicksort::sift_down`], but all comparisons are done before moving, so the heap is unchanged on error.
+    fn sift_down_within(&mut self, node: usize, len: usize) -> OrderResult<()> {
+        let v = &self.data[..len];
+        let mut hole = node;
+        loop {
+            let left = 2 * hole + 1;
+            let right = 2 * hole + 2;
+            if left >= len {
+                break;
+            }
+            let greater = if right < len && is_less(&mut self.compare, &v[left], &v[right])? {
+                right
+            } else {
+                left
+            };
+            if !is_less(&mut self.compare, &v[node], &v[greater])? {
+                break;
+            }
+            hole = greater;
+        }
+        // Rotate the path from `node` to `hole`, so that the element at `node` goes to `hole`.
+        let mut parent = hole;
+        while parent != node {
+            parent = (parent - 1) / 2;
+            self.data.swap(hole, parent);
+        }
+        Ok(())
+    }
+}
+
+impl<T, C> TryBinaryHeap<T, C> {
+    /// Returns the number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if there is no element.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the iterator visiting all elements in arbitrary order.
+    #[inline]
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Removes all elements.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns the elements in arbitrary order.
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+}
+
+impl<T: Debug, C> Debug for TryBinaryHeap<T, C> {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        fmt.debug_list().entries(self.data.iter()).finish()
+    }
+}
+
+fn is_less<T, C>(compare: &mut C, a: &T, b: &T) -> OrderResult<bool>
+where
+    C: FnMut(&T, &T) -> Option<Ordering>,
+{
+    compare(a, b)
+        .map(|o| o == Ordering::Less)
+        .ok_or_else(heap_error)
+}
+
+fn heap_error() -> InvalidOrderError {
+    InvalidOrderError::new(OrderOperation::Heap)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::distributions::Standard;
+    use rand::prelude::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn try_binary_heap_ok() {
+        let rng = thread_rng();
+        let v: Vec<f64> = Standard.sample_iter(rng).take(100).collect();
+        let mut sorted = v.clone();
+        sorted.try_sort().unwrap();
+
+        let mut heap = TryBinaryHeap::new();
+        for x in &v {
+            heap.try_push(*x).unwrap();
+        }
+        let mut popped = Vec::new();
+        while let Some(x) = heap.try_pop().unwrap() {
+            popped.push(x);
+        }
+        popped.reverse();
+        assert_eq!(popped, sorted);
+
+        let heap = TryBinaryHeap::try_from_vec_min(v.clone()).unwrap();
+        assert_eq!(heap.peek(), sorted.first());
+        let mut descending = heap.try_into_sorted_vec().unwrap();
+        descending.reverse();
+        assert_eq!(descending, sorted);
+        let heap = TryBinaryHeap::try_from_vec(v).unwrap();
+        assert_eq!(heap.try_into_sorted_vec().unwrap(), sorted);
+    }
+
+    #[test]
+    fn try_binary_heap_error() {
+        let mut heap = TryBinaryHeap::try_from_vec(vec![3.0, 1.0, 4.0, 1.0, 5.0]).unwrap();
+        let err = heap.try_push(f64::NAN).unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::Heap);
+        assert_eq!(heap.len(), 5);
+        assert_eq!(heap.try_peek_mut(|x| *x = 0.0), Ok(Some(())));
+        assert_eq!(heap.peek(), Some(&4.0));
+        assert!(heap.try_peek_mut(|x| *x = f64::NAN).is_err());
+        assert_eq!(heap.len(), 4);
+        let rest = heap.try_into_sorted_vec().unwrap();
+        assert_eq!(rest, [0.0, 1.0, 1.0, 3.0]);
+        assert!(TryBinaryHeap::try_from_vec(vec![f64::NAN]).is_err());
+        assert!(TryBinaryHeap::try_from_vec(vec![1.0, f64::NAN]).is_err());
+    }
+}
@@ -1,9 +1,34 @@
-use crate::{InvalidOrderError, OrderResult};
+use crate::{InvalidOrderError, OrderOperation, OrderResult};
+use core::borrow::Borrow;
 use core::cmp::Ordering;
+use core::ops::{Bound, Range, RangeBounds, Sub};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// Which element to choose in [`try_nearest`](`TryBinarySearch::try_nearest`) when two elements are at the same distance.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub enum NearestTie {
+    /// The smaller element.
+    #[default]
+    Lower,
+    /// The larger element.
+    Higher,
+}
+
+/// Which side of the equal elements [`try_searchsorted`](`TryBinarySearch::try_searchsorted`) returns, the same as `numpy.searchsorted`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub enum Side {
+    /// The first index where the query can be inserted, like [`try_lower_bound`](`TryBinarySearch::try_lower_bound`).
+    #[default]
+    Left,
+    /// The last index where the query can be inserted, like [`try_upper_bound`](`TryBinarySearch::try_upper_bound`).
+    Right,
+}
 
 /// Binary Search methods for [`PartialOrd`].
 ///
 /// Caution! This might not return error even if there is invalid order value (like [`f32::NAN`]), because including these value means that it is not sorted correctly and we cannot ensure the return value of binary_search for unsorted slice.
+/// Use [`try_binary_search_checked`](`TryBinarySearch::try_binary_search_checked`) to detect some of these cases.
 pub trait TryBinarySearch<T> {
     ///[`PartialOrd`] version for [`slice::binary_search`]
     #[inline]
@@ -13,10 +38,62 @@ pub trait TryBinarySearch<T> {
     {
         self.try_binary_search_by(|a| a.partial_cmp(x))
     }
+    #[inline]
+    ///Binary search with the probe of another type, which the elements can be compared with.
+    ///```
+    ///use try_partialord::*;
+    ///
+    ///#[derive(PartialEq)]
+    ///struct Measurement {
+    ///    time: f64,
+    ///}
+    ///impl PartialEq<f64> for Measurement {
+    ///    fn eq(&self, other: &f64) -> bool {
+    ///        self.time == *other
+    ///    }
+    ///}
+    ///impl PartialOrd<f64> for Measurement {
+    ///    fn partial_cmp(&self, other: &f64) -> Option<core::cmp::Ordering> {
+    ///        self.time.partial_cmp(other)
+    ///    }
+    ///}
+    ///
+    ///let v = [Measurement { time: 1.0 }, Measurement { time: 2.5 }];
+    ///assert_eq!(v.try_binary_search_with(&2.5), Ok(Ok(1)));
+    ///assert!(v.try_binary_search_with(&f64::NAN).is_err());
+    ///```
+    fn try_binary_search_with<Q>(&self, q: &Q) -> OrderResult<Result<usize, usize>>
+    where
+        T: PartialOrd<Q>,
+        Q: ?Sized,
+    {
+        self.try_binary_search_by(|a| a.partial_cmp(q))
+    }
+    #[inline]
+    ///Binary search with the borrowed form of the elements, like [`BTreeMap::get`](`std::collections::BTreeMap::get`).
+    ///```
+    ///use try_partialord::*;
+    ///
+    ///let v = vec![String::from("a"), String::from("c")];
+    ///assert_eq!(v.try_binary_search_borrow("b"), Ok(Err(1)));
+    ///```
+    fn try_binary_search_borrow<Q>(&self, q: &Q) -> OrderResult<Result<usize, usize>>
+    where
+        T: Borrow<Q>,
+        Q: PartialOrd<Q> + ?Sized,
+    {
+        self.try_binary_search_by(|a| a.borrow().partial_cmp(q))
+    }
     ///[`PartialOrd`] version for [`slice::binary_search_by`]
     fn try_binary_search_by<F>(&self, compare: F) -> OrderResult<Result<usize, usize>>
     where
         F: FnMut(&T) -> Option<Ordering>;
+    ///Version of [`slice::binary_search_by`] with the comparator which can fail with your own error.
+    ///
+    ///The error returned by `compare` is returned as is.
+    fn try_binary_search_by_result<E, F>(&self, compare: F) -> Result<Result<usize, usize>, E>
+    where
+        F: FnMut(&T) -> Result<Ordering, E>;
     #[inline]
     ///[`PartialOrd`] version for [`slice::binary_search_by_key`]
     fn try_binary_search_by_key<K, F>(&self, b: &K, f: F) -> OrderResult<Result<usize, usize>>
@@ -27,6 +104,327 @@ pub trait TryBinarySearch<T> {
         let mut fk = f;
         self.try_binary_search_by(|a| fk(a)?.partial_cmp(b))
     }
+
+    ///Same as [`try_binary_search`](`TryBinarySearch::try_binary_search`), but also checks that each probed element is
+    ///not less than the element before it, returning error with [`is_unsorted`](`InvalidOrderError::is_unsorted`) if it is.
+    ///
+    ///This still touches only `O(log n)` elements, so it cannot find every unsorted slice.
+    ///With `debug_assertions`, the whole slice is checked first in `O(n)`.
+    ///```
+    ///use try_partialord::*;
+    ///
+    ///let v = [1.0, 2.0, 3.0, 0.5, 5.0];
+    ///assert_eq!(v.try_binary_search(&4.0), Ok(Err(4)));
+    ///let err = v.try_binary_search_checked(&4.0).unwrap_err();
+    ///assert!(err.is_unsorted());
+    ///assert_eq!(err.indices(), Some((2, 3)));
+    ///
+    ///let v = [1.0, f64::NAN, 3.0];
+    ///assert!(!v.try_binary_search_checked(&3.0).unwrap_err().is_unsorted());
+    ///```
+    fn try_binary_search_checked(&self, x: &T) -> OrderResult<Result<usize, usize>>
+    where
+        T: PartialOrd<T>;
+    ///Checked version of [`try_binary_search_by_key`](`TryBinarySearch::try_binary_search_by_key`), comparing the keys of the neighbors.
+    fn try_binary_search_checked_by_key<K, F>(
+        &self,
+        b: &K,
+        f: F,
+    ) -> OrderResult<Result<usize, usize>>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>;
+
+    #[cfg(feature = "std")]
+    #[inline]
+    ///Searches each of `queries`, returning the results in the same order.
+    ///
+    ///When `queries` are sorted, each search gallops forward from the previous result with
+    ///[`try_exponential_search_from_by`](`TryBinarySearch::try_exponential_search_from_by`), so the total cost is
+    ///`O(m log(n/m))` instead of `O(m log n)`. Queries which are not sorted fall back to the full binary search.
+    ///For duplicates, the matching index is any of them, same as [`try_binary_search`](`TryBinarySearch::try_binary_search`).
+    ///
+    ///Each query is checked to be comparable with itself, so NaN fails even with an empty slice.
+    ///The index of the error is the position in `queries`, the same as [`try_searchsorted`](`TryBinarySearch::try_searchsorted`).
+    ///```
+    ///use try_partialord::*;
+    ///
+    ///let table = [1.0, 2.0, 3.0, 5.0, 8.0];
+    ///assert_eq!(
+    ///    table.try_binary_search_many(&[0.5, 2.0, 4.0, 9.0, 3.0]),
+    ///    Ok(vec![Err(0), Ok(1), Err(3), Err(5), Ok(2)])
+    ///);
+    ///assert_eq!(table.try_binary_search_many(&[1.0, f64::NAN]).unwrap_err().index(), Some(1));
+    ///assert!([0.0; 0].try_binary_search_many(&[f64::NAN]).is_err());
+    ///```
+    fn try_binary_search_many(&self, queries: &[T]) -> OrderResult<Vec<Result<usize, usize>>>
+    where
+        T: PartialOrd<T>,
+    {
+        let mut out = vec![Err(0); queries.len()];
+        self.try_binary_search_many_into(queries, &mut out)?;
+        Ok(out)
+    }
+    #[cfg(feature = "std")]
+    ///Returns the indices where each of `queries` can be inserted keeping the order, the same as `numpy.searchsorted`.
+    ///
+    ///The whole slice is checked first in `O(n)`, because a value like NaN which binary search never probes would make the result wrong.
+    ///Errors about the slice have [`OrderOperation::IsSorted`] with the positions in the slice,
+    ///and errors about `queries`, like NaN, have [`OrderOperation::BinarySearch`] with the position in `queries`.
+    ///```
+    ///use try_partialord::*;
+    ///
+    ///let edges = [1.0, 2.0, 2.0, 3.0];
+    ///assert_eq!(edges.try_searchsorted(&[2.0, 0.0, 5.0], Side::Left), Ok(vec![1, 0, 4]));
+    ///assert_eq!(edges.try_searchsorted(&[2.0, 0.0, 5.0], Side::Right), Ok(vec![3, 0, 4]));
+    ///
+    ///let err = edges.try_searchsorted(&[0.5, f64::NAN], Side::Left).unwrap_err();
+    ///assert_eq!((err.operation(), err.index()), (OrderOperation::BinarySearch, Some(1)));
+    ///let err = [1.0, 2.0, f64::NAN].try_searchsorted(&[0.5], Side::Left).unwrap_err();
+    ///assert_eq!((err.operation(), err.index()), (OrderOperation::IsSorted, Some(2)));
+    ///```
+    fn try_searchsorted(&self, queries: &[T], side: Side) -> OrderResult<Vec<usize>>
+    where
+        T: PartialOrd<T>;
+    ///Same as [`try_binary_search_many`](`TryBinarySearch::try_binary_search_many`), but writes the results into `out`.
+    ///
+    ///Panics if `out` has a different length from `queries`.
+    fn try_binary_search_many_into(
+        &self,
+        queries: &[T],
+        out: &mut [Result<usize, usize>],
+    ) -> OrderResult<()>
+    where
+        T: PartialOrd<T>;
+
+    #[inline]
+    ///Exponential search, which is faster than binary search when the target is near the start.
+    ///
+    ///It probes the indices `0`, `1`, `2`, `4`, `8`, ... and bisects between the last two probes, so it takes
+    ///`O(log i)` comparisons where `i` is the result. The result is the same shape as [`try_binary_search`](`TryBinarySearch::try_binary_search`).
+    ///```
+    ///use try_partialord::*;
+    ///
+    ///let v = [1.0, 2.0, 3.0, 5.0, 8.0, 13.0];
+    ///assert_eq!(v.try_exponential_search(&3.0), Ok(Ok(2)));
+    ///assert_eq!(v.try_exponential_search_from(4, &4.0), Ok(Err(3)));
+    ///assert_eq!(v.try_exponential_search_from(1, &20.0), Ok(Err(6)));
+    ///assert!(v.try_exponential_search(&f64::NAN).is_err());
+    ///```
+    fn try_exponential_search(&self, x: &T) -> OrderResult<Result<usize, usize>>
+    where
+        T: PartialOrd<T>,
+    {
+        self.try_exponential_search_by(|a| a.partial_cmp(x))
+    }
+    #[inline]
+    ///Exponential search with the comparator, the same as [`try_binary_search_by`](`TryBinarySearch::try_binary_search_by`).
+    fn try_exponential_search_by<F>(&self, compare: F) -> OrderResult<Result<usize, usize>>
+    where
+        F: FnMut(&T) -> Option<Ordering>,
+    {
+        self.try_exponential_search_from_by(0, compare)
+    }
+    #[inline]
+    ///Exponential search starting from `hint`, galloping in either direction before bisecting.
+    ///
+    ///It probes `hint`, then `hint + 1`, `hint + 2`, `hint + 4`, ... or `hint - 1`, `hint - 2`, `hint - 4`, ... toward the target.
+    ///
+    ///It takes `O(log d)` comparisons where `d` is the distance from `hint` to the result. `hint` larger than the length is allowed.
+    fn try_exponential_search_from(&self, hint: usize, x: &T) -> OrderResult<Result<usize, usize>>
+    where
+        T: PartialOrd<T>,
+    {
+        self.try_exponential_search_from_by(hint, |a| a.partial_cmp(x))
+    }
+    ///Exponential search with the comparator starting from `hint`.
+    fn try_exponential_search_from_by<F>(
+        &self,
+        hint: usize,
+        compare: F,
+    ) -> OrderResult<Result<usize, usize>>
+    where
+        F: FnMut(&T) -> Option<Ordering>;
+
+    #[inline]
+    ///Returns the index of the element nearest to `x`, or [`None`] if empty.
+    ///
+    ///The distance is `x - a` for the element `a` before `x` and `b - x` for the element `b` after it, found by binary search.
+    ///```
+    ///use try_partialord::*;
+    ///
+    ///let times = [0.0, 1.0, 2.0, 4.0];
+    ///assert_eq!(times.try_nearest(&1.4, NearestTie::Lower), Ok(Some(1)));
+    ///assert_eq!(times.try_nearest(&3.0, NearestTie::Lower), Ok(Some(2)));
+    ///assert_eq!(times.try_nearest(&3.0, NearestTie::Higher), Ok(Some(3)));
+    ///assert_eq!(times.try_k_nearest(&1.4, 3, NearestTie::Lower), Ok(0..3));
+    ///assert!(times.try_nearest(&f64::NAN, NearestTie::Lower).is_err());
+    ///```
+    fn try_nearest(&self, x: &T, tie: NearestTie) -> OrderResult<Option<usize>>
+    where
+        T: PartialOrd<T> + Copy + Sub<Output = T>,
+    {
+        let range = self.try_k_nearest(x, 1, tie)?;
+        Ok(Some(range.start).filter(|_| !range.is_empty()))
+    }
+    #[inline]
+    ///Returns the index of the element whose key is nearest to `b`, or [`None`] if empty.
+    fn try_nearest_by_key<K, F>(&self, b: &K, f: F, tie: NearestTie) -> OrderResult<Option<usize>>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K> + Copy + Sub<Output = K>,
+    {
+        let range = self.try_k_nearest_by_key(b, 1, f, tie)?;
+        Ok(Some(range.start).filter(|_| !range.is_empty()))
+    }
+    ///Returns the range of the `k` elements nearest to `x`, expanding outward from the insertion point.
+    ///
+    ///The range is shorter than `k` only if the slice is.
+    fn try_k_nearest(&self, x: &T, k: usize, tie: NearestTie) -> OrderResult<Range<usize>>
+    where
+        T: PartialOrd<T> + Copy + Sub<Output = T>;
+    ///Returns the range of the `k` elements whose keys are nearest to `b`.
+    fn try_k_nearest_by_key<K, F>(
+        &self,
+        b: &K,
+        k: usize,
+        f: F,
+        tie: NearestTie,
+    ) -> OrderResult<Range<usize>>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K> + Copy + Sub<Output = K>;
+
+    ///[`PartialOrd`] version for [`slice::partition_point`]
+    ///
+    ///Returns the index of the first element for which `pred` returns `Some(false)`, assuming the slice is partitioned by `pred`.
+    ///```
+    ///use try_partialord::*;
+    ///
+    ///let v = [1.0, 2.0, 2.0, 2.0, 3.0];
+    ///assert_eq!(v.try_partition_point(|x| Some(*x < 2.5)), Ok(4));
+    ///assert_eq!(v.try_lower_bound(&2.0), Ok(1));
+    ///assert_eq!(v.try_upper_bound(&2.0), Ok(4));
+    ///assert_eq!(v.try_equal_range(&2.0), Ok(1..4));
+    ///assert!(v.try_equal_range(&f64::NAN).is_err());
+    ///```
+    fn try_partition_point<P>(&self, pred: P) -> OrderResult<usize>
+    where
+        P: FnMut(&T) -> Option<bool>;
+    #[inline]
+    ///Returns the index of the first element which is not less than `x`.
+    fn try_lower_bound(&self, x: &T) -> OrderResult<usize>
+    where
+        T: PartialOrd<T>,
+    {
+        self.try_lower_bound_by(|a| a.partial_cmp(x))
+    }
+    #[inline]
+    ///Returns the index of the first element for which `compare` does not return [`Ordering::Less`].
+    ///
+    ///`compare` is the same as [`try_binary_search_by`](`TryBinarySearch::try_binary_search_by`).
+    fn try_lower_bound_by<F>(&self, compare: F) -> OrderResult<usize>
+    where
+        F: FnMut(&T) -> Option<Ordering>,
+    {
+        let mut compare = compare;
+        self.try_partition_point(|a| compare(a).map(Ordering::is_lt))
+    }
+    #[inline]
+    ///Returns the index of the first element whose key is not less than `b`.
+    fn try_lower_bound_by_key<K, F>(&self, b: &K, f: F) -> OrderResult<usize>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>,
+    {
+        let mut fk = f;
+        self.try_lower_bound_by(|a| fk(a)?.partial_cmp(b))
+    }
+    #[inline]
+    ///Returns the index of the first element which is greater than `x`.
+    fn try_upper_bound(&self, x: &T) -> OrderResult<usize>
+    where
+        T: PartialOrd<T>,
+    {
+        self.try_upper_bound_by(|a| a.partial_cmp(x))
+    }
+    #[inline]
+    ///Returns the index of the first element for which `compare` returns [`Ordering::Greater`].
+    fn try_upper_bound_by<F>(&self, compare: F) -> OrderResult<usize>
+    where
+        F: FnMut(&T) -> Option<Ordering>,
+    {
+        let mut compare = compare;
+        self.try_partition_point(|a| compare(a).map(Ordering::is_le))
+    }
+    #[inline]
+    ///Returns the index of the first element whose key is greater than `b`.
+    fn try_upper_bound_by_key<K, F>(&self, b: &K, f: F) -> OrderResult<usize>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>,
+    {
+        let mut fk = f;
+        self.try_upper_bound_by(|a| fk(a)?.partial_cmp(b))
+    }
+    #[inline]
+    ///Returns the range of the elements equal to `x`, which is empty at the insertion point if there is not.
+    fn try_equal_range(&self, x: &T) -> OrderResult<Range<usize>>
+    where
+        T: PartialOrd<T>,
+    {
+        self.try_equal_range_by(|a| a.partial_cmp(x))
+    }
+    #[inline]
+    ///Returns the range of the elements for which `compare` returns [`Ordering::Equal`].
+    fn try_equal_range_by<F>(&self, compare: F) -> OrderResult<Range<usize>>
+    where
+        F: FnMut(&T) -> Option<Ordering>,
+    {
+        let mut compare = compare;
+        Ok(self.try_lower_bound_by(&mut compare)?..self.try_upper_bound_by(&mut compare)?)
+    }
+    #[inline]
+    ///Returns the range of the elements whose keys are equal to `b`.
+    fn try_equal_range_by_key<K, F>(&self, b: &K, f: F) -> OrderResult<Range<usize>>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>,
+    {
+        let mut fk = f;
+        self.try_equal_range_by(|a| fk(a)?.partial_cmp(b))
+    }
+    ///Returns the elements in `range`, which is the range of the values comparable with the elements, found by two binary searches.
+    ///
+    ///It returns error if a bound cannot be compared with the probed elements, like [`f64::NAN`].
+    ///```
+    ///use try_partialord::*;
+    ///
+    ///let t = [0.5, 1.5, 2.0, 3.0, 3.0, 4.5];
+    ///assert_eq!(t.try_range(1.5..3.0), Ok(&[1.5, 2.0][..]));
+    ///assert_eq!(t.try_range(1.5..=3.0), Ok(&[1.5, 2.0, 3.0, 3.0][..]));
+    ///assert_eq!(t.try_range(4.0..), Ok(&[4.5][..]));
+    ///assert_eq!(t.try_range(3.0..1.0), Ok(&[][..]));
+    ///assert!(t.try_range(..f64::NAN).is_err());
+    ///```
+    fn try_range<Q, R>(&self, range: R) -> OrderResult<&[T]>
+    where
+        T: PartialOrd<Q>,
+        Q: ?Sized,
+        R: RangeBounds<Q>;
+    ///Same as [`try_range`](`TryBinarySearch::try_range`), but returns the mutable elements.
+    ///```
+    ///use try_partialord::*;
+    ///
+    ///let mut t = [0.5, 1.5, 2.0, 3.0];
+    ///t.try_range_mut(1.0..2.5).unwrap().iter_mut().for_each(|a| *a += 0.25);
+    ///assert_eq!(t, [0.5, 1.75, 2.25, 3.0]);
+    ///```
+    fn try_range_mut<Q, R>(&mut self, range: R) -> OrderResult<&mut [T]>
+    where
+        T: PartialOrd<Q>,
+        Q: ?Sized,
+        R: RangeBounds<Q>;
 }
 
 impl<T> TryBinarySearch<T> for [T] {
@@ -35,13 +433,382 @@ impl<T> TryBinarySearch<T> for [T] {
     where
         F: FnMut(&T) -> Option<Ordering>,
     {
-        try_binary_search_by_inner(self, compare).ok_or(InvalidOrderError)
+        let mut compare = compare;
+        try_binary_search_by_inner(self, |_, a| compare(a).ok_or(()))
+            .map_err(|((), i)| InvalidOrderError::with_index(OrderOperation::BinarySearch, i))
+    }
+
+    #[inline]
+    fn try_binary_search_by_result<E, F>(&self, compare: F) -> Result<Result<usize, usize>, E>
+    where
+        F: FnMut(&T) -> Result<Ordering, E>,
+    {
+        let mut compare = compare;
+        try_binary_search_by_inner(self, |_, a| compare(a)).map_err(|(e, _)| e)
+    }
+
+    #[inline]
+    fn try_binary_search_checked(&self, x: &T) -> OrderResult<Result<usize, usize>>
+    where
+        T: PartialOrd<T>,
+    {
+        try_binary_search_checked_inner(self, &x, Some)
     }
+
+    #[inline]
+    fn try_binary_search_checked_by_key<K, F>(
+        &self,
+        b: &K,
+        f: F,
+    ) -> OrderResult<Result<usize, usize>>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>,
+    {
+        try_binary_search_checked_inner(self, b, f)
+    }
+
+    #[inline]
+    fn try_k_nearest(&self, x: &T, k: usize, tie: NearestTie) -> OrderResult<Range<usize>>
+    where
+        T: PartialOrd<T> + Copy + Sub<Output = T>,
+    {
+        try_k_nearest_inner(self, *x, k, |a| Some(*a), tie)
+    }
+
+    #[inline]
+    fn try_k_nearest_by_key<K, F>(
+        &self,
+        b: &K,
+        k: usize,
+        f: F,
+        tie: NearestTie,
+    ) -> OrderResult<Range<usize>>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K> + Copy + Sub<Output = K>,
+    {
+        try_k_nearest_inner(self, *b, k, f, tie)
+    }
+
+    #[inline]
+    fn try_exponential_search_from_by<F>(
+        &self,
+        hint: usize,
+        compare: F,
+    ) -> OrderResult<Result<usize, usize>>
+    where
+        F: FnMut(&T) -> Option<Ordering>,
+    {
+        try_exponential_search_inner(self, hint, compare)
+    }
+
+    #[cfg(feature = "std")]
+    fn try_searchsorted(&self, queries: &[T], side: Side) -> OrderResult<Vec<usize>>
+    where
+        T: PartialOrd<T>,
+    {
+        check_edges(self)?;
+        queries
+            .iter()
+            .enumerate()
+            .map(|(i, q)| {
+                let error = InvalidOrderError::with_index(OrderOperation::BinarySearch, i);
+                if q.partial_cmp(q).is_none() {
+                    return Err(error);
+                }
+                match side {
+                    Side::Left => self.try_lower_bound(q),
+                    Side::Right => self.try_upper_bound(q),
+                }
+                .map_err(|_| error)
+            })
+            .collect()
+    }
+
+    fn try_binary_search_many_into(
+        &self,
+        queries: &[T],
+        out: &mut [Result<usize, usize>],
+    ) -> OrderResult<()>
+    where
+        T: PartialOrd<T>,
+    {
+        assert_eq!(
+            queries.len(),
+            out.len(),
+            "output length is different from queries"
+        );
+        let mut prev: Option<(&T, usize)> = None;
+        for (i, (q, o)) in queries.iter().zip(out.iter_mut()).enumerate() {
+            let error = InvalidOrderError::with_index(OrderOperation::BinarySearch, i);
+            if q.partial_cmp(q).is_none() {
+                return Err(error);
+            }
+            let found = match prev {
+                // The result is after the previous one, so gallop from there.
+                Some((p, finger)) if p <= q => {
+                    self.try_exponential_search_from_by(finger, |a| a.partial_cmp(q))
+                }
+                _ => self.try_binary_search(q),
+            }
+            .map_err(|_| error)?;
+            *o = found;
+            prev = Some((q, found.unwrap_or_else(|i| i)));
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn try_partition_point<P>(&self, pred: P) -> OrderResult<usize>
+    where
+        P: FnMut(&T) -> Option<bool>,
+    {
+        // `compare` never returns `Equal`, so the search always ends at the partition point.
+        let mut pred = pred;
+        self.try_binary_search_by(|a| {
+            pred(a).map(|p| if p { Ordering::Less } else { Ordering::Greater })
+        })
+        .map(|found| found.unwrap_or_else(|i| i))
+    }
+
+    #[inline]
+    fn try_range<Q, R>(&self, range: R) -> OrderResult<&[T]>
+    where
+        T: PartialOrd<Q>,
+        Q: ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let range = try_range_indices(self, range, |a| a)?;
+        Ok(&self[range])
+    }
+
+    #[inline]
+    fn try_range_mut<Q, R>(&mut self, range: R) -> OrderResult<&mut [T]>
+    where
+        T: PartialOrd<Q>,
+        Q: ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let range = try_range_indices(self, range, |a| a)?;
+        Ok(&mut self[range])
+    }
+}
+
+/// Checks that `edges` are sorted and each of them can be compared with itself.
+///
+/// The error has [`OrderOperation::IsSorted`] with the positions in `edges`.
+#[cfg(feature = "std")]
+pub(crate) fn check_edges<T: PartialOrd<T>>(edges: &[T]) -> OrderResult<()> {
+    let op = OrderOperation::IsSorted;
+    if let Some(i) = edges.iter().position(|x| x.partial_cmp(x).is_none()) {
+        return Err(InvalidOrderError::with_index(op, i));
+    }
+    for i in 1..edges.len() {
+        match edges[i - 1].partial_cmp(&edges[i]) {
+            Some(Ordering::Greater) => return Err(InvalidOrderError::unsorted(op, i - 1, i)),
+            Some(_) => {}
+            None => return Err(InvalidOrderError::with_indices(op, i - 1, i)),
+        }
+    }
+    Ok(())
+}
+
+/// Returns the indices of the elements whose keys are in `range`. The start is never larger than the end.
+pub(crate) fn try_range_indices<T, K, Q, R, F>(
+    v: &[T],
+    range: R,
+    key: F,
+) -> OrderResult<Range<usize>>
+where
+    K: PartialOrd<Q> + ?Sized,
+    Q: ?Sized,
+    R: RangeBounds<Q>,
+    F: Fn(&T) -> &K,
+{
+    let start = match range.start_bound() {
+        Bound::Included(x) => {
+            v.try_partition_point(|a| key(a).partial_cmp(x).map(Ordering::is_lt))?
+        }
+        Bound::Excluded(x) => {
+            v.try_partition_point(|a| key(a).partial_cmp(x).map(Ordering::is_le))?
+        }
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(x) => {
+            v.try_partition_point(|a| key(a).partial_cmp(x).map(Ordering::is_le))?
+        }
+        Bound::Excluded(x) => {
+            v.try_partition_point(|a| key(a).partial_cmp(x).map(Ordering::is_lt))?
+        }
+        Bound::Unbounded => v.len(),
+    };
+    Ok(start..end.max(start))
+}
+
+/// Finds the insertion point of `x` and expands the range to the nearer side `k` times.
+fn try_k_nearest_inner<T, K, F>(
+    slice: &[T],
+    x: K,
+    k: usize,
+    mut key: F,
+    tie: NearestTie,
+) -> OrderResult<Range<usize>>
+where
+    F: FnMut(&T) -> Option<K>,
+    K: PartialOrd<K> + Copy + Sub<Output = K>,
+{
+    let op = OrderOperation::BinarySearch;
+    let start = slice.try_partition_point(|a| key(a)?.partial_cmp(&x).map(Ordering::is_lt))?;
+    // Every element added to the range must be comparable with `x`, even when there is no other side.
+    let mut key = |i: usize| {
+        key(&slice[i])
+            .filter(|a| a.partial_cmp(&x).is_some())
+            .ok_or(InvalidOrderError::with_index(op, i))
+    };
+    let (mut lo, mut hi) = (start, start);
+    while hi - lo < k && (lo > 0 || hi < slice.len()) {
+        let take_lower = if lo == 0 {
+            key(hi)?;
+            false
+        } else if hi == slice.len() {
+            key(lo - 1)?;
+            true
+        } else {
+            let (a, b) = (key(lo - 1)?, key(hi)?);
+            // An equal element is at distance zero, which cannot be subtracted for infinities.
+            let ord = match (a == x, b == x) {
+                (true, true) => Some(Ordering::Equal),
+                (true, false) => Some(Ordering::Less),
+                (false, true) => Some(Ordering::Greater),
+                (false, false) => (x - a).partial_cmp(&(b - x)),
+            };
+            match ord {
+                Some(Ordering::Less) => true,
+                Some(Ordering::Greater) => false,
+                Some(Ordering::Equal) => tie == NearestTie::Lower,
+                None => return Err(InvalidOrderError::with_indices(op, lo - 1, hi)),
+            }
+        };
+        if take_lower {
+            lo -= 1;
+        } else {
+            hi += 1;
+        }
+    }
+    Ok(lo..hi)
 }
 
-fn try_binary_search_by_inner<T, F>(slice: &[T], mut compare: F) -> Option<Result<usize, usize>>
+/// Gallops from `hint` in the direction of the target, then bisects between the last two probes.
+fn try_exponential_search_inner<T, F>(
+    slice: &[T],
+    hint: usize,
+    mut compare: F,
+) -> OrderResult<Result<usize, usize>>
 where
     F: FnMut(&T) -> Option<Ordering>,
+{
+    if slice.is_empty() {
+        return Ok(Err(0));
+    }
+    let mut probe = |i: usize| {
+        compare(&slice[i]).ok_or(InvalidOrderError::with_index(
+            OrderOperation::BinarySearch,
+            i,
+        ))
+    };
+    let hint = hint.min(slice.len() - 1);
+    // The target is in `lo..hi` after galloping.
+    let (lo, hi) = match probe(hint)? {
+        Ordering::Equal => return Ok(Ok(hint)),
+        Ordering::Less => {
+            let (mut lo, mut step) = (hint + 1, 1);
+            loop {
+                let i = hint + step;
+                if i >= slice.len() {
+                    break (lo, slice.len());
+                }
+                match probe(i)? {
+                    Ordering::Less => lo = i + 1,
+                    Ordering::Equal => return Ok(Ok(i)),
+                    Ordering::Greater => break (lo, i),
+                }
+                step *= 2;
+            }
+        }
+        Ordering::Greater => {
+            let (mut hi, mut step) = (hint, 1);
+            loop {
+                if step > hint {
+                    break (0, hi);
+                }
+                let i = hint - step;
+                match probe(i)? {
+                    Ordering::Greater => hi = i,
+                    Ordering::Equal => return Ok(Ok(i)),
+                    Ordering::Less => break (i + 1, hi),
+                }
+                step *= 2;
+            }
+        }
+    };
+    match try_binary_search_by_inner(&slice[lo..hi], |_, a| compare(a).ok_or(())) {
+        Ok(found) => Ok(found.map(|i| lo + i).map_err(|i| lo + i)),
+        Err(((), i)) => Err(InvalidOrderError::with_index(
+            OrderOperation::BinarySearch,
+            lo + i,
+        )),
+    }
+}
+
+/// Binary search comparing the key of each probed element with the key of the element before it.
+fn try_binary_search_checked_inner<'a, T, K, F>(
+    slice: &'a [T],
+    b: &K,
+    mut f: F,
+) -> OrderResult<Result<usize, usize>>
+where
+    F: FnMut(&'a T) -> Option<K>,
+    K: PartialOrd<K>,
+{
+    let op = OrderOperation::BinarySearch;
+    let mut key = |i: usize| f(&slice[i]).ok_or(InvalidOrderError::with_index(op, i));
+    if cfg!(debug_assertions) {
+        for i in 1..slice.len() {
+            match key(i - 1)?.partial_cmp(&key(i)?) {
+                Some(Ordering::Greater) => return Err(InvalidOrderError::unsorted(op, i - 1, i)),
+                Some(_) => {}
+                None => return Err(InvalidOrderError::with_indices(op, i - 1, i)),
+            }
+        }
+    }
+    try_binary_search_by_inner(slice, |mid, _| {
+        let k = key(mid)?;
+        if mid > 0 {
+            match key(mid - 1)?.partial_cmp(&k) {
+                Some(Ordering::Greater) => {
+                    return Err(InvalidOrderError::unsorted(op, mid - 1, mid))
+                }
+                Some(_) => {}
+                None => return Err(InvalidOrderError::with_indices(op, mid - 1, mid)),
+            }
+        }
+        k.partial_cmp(b)
+            .ok_or(InvalidOrderError::with_index(op, mid))
+    })
+    .map_err(|(e, _)| e)
+}
+
+/// Binary search returning the error with the index of the probe on failure.
+///
+/// `compare` takes the index of the probe too.
+fn try_binary_search_by_inner<T, E, F>(
+    slice: &[T],
+    mut compare: F,
+) -> Result<Result<usize, usize>, (E, usize)>
+where
+    F: FnMut(usize, &T) -> Result<Ordering, E>,
 {
     let mut size = slice.len();
     let mut left = 0;
@@ -52,7 +819,7 @@ where
         // SAFETY: the call is made safe by the following invariants:
         // - `mid >= 0`
         // - `mid < size`: `mid` is limited by `[left; right)` bound.
-        let cmp = compare(unsafe { slice.get_unchecked(mid) })?;
+        let cmp = compare(mid, unsafe { slice.get_unchecked(mid) }).map_err(|e| (e, mid))?;
 
         // The reason why we use if/else control flow rather than match
         // is because match reorders comparison operations, which is perf sensitive.
@@ -65,12 +832,12 @@ where
         } else {
             // SAFETY: same as the `get_unchecked` above
             //unsafe { core::intrinsics::assume(mid < slice.len()) };
-            return Some(Ok(mid));
+            return Ok(Ok(mid));
         }
 
         size = right - left;
     }
-    Some(Err(left))
+    Ok(Err(left))
 }
 
 #[cfg(test)]
@@ -98,4 +865,221 @@ mod tests {
             assert!(sm >= &b);
         }
     }
+
+    #[test]
+    fn try_binary_search_error() {
+        let v = [1.0, 2.0, f32::NAN, 4.0, 5.0];
+        let err = v.try_binary_search(&4.0).unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::BinarySearch);
+        assert_eq!(err.index(), Some(2));
+    }
+
+    #[test]
+    fn try_equal_range_duplicates() {
+        let rng = thread_rng();
+        let mut v: Vec<f32> = Standard
+            .sample_iter(rng)
+            .take(200)
+            .map(|x: f32| (x * 10.0).floor())
+            .collect();
+        v.try_sort().unwrap();
+        for x in 0..10 {
+            let x = x as f32;
+            let range = v.try_equal_range(&x).unwrap();
+            assert!(v[..range.start].iter().all(|a| *a < x));
+            assert!(v[range.clone()].iter().all(|a| *a == x));
+            assert!(v[range.end..].iter().all(|a| *a > x));
+            assert_eq!(v.try_lower_bound_by_key(&x, |a| Some(*a)), Ok(range.start));
+            assert_eq!(v.try_upper_bound_by_key(&x, |a| Some(*a)), Ok(range.end));
+        }
+        let v = [1.0, 2.0, f32::NAN, 4.0, 5.0];
+        let err = v.try_upper_bound(&3.0).unwrap_err();
+        assert_eq!(err.index(), Some(2));
+    }
+
+    #[test]
+    fn try_binary_search_checked() {
+        let rng = thread_rng();
+        let mut v: Vec<f32> = Standard.sample_iter(rng).take(100).collect();
+        v.try_sort().unwrap();
+        for x in v.iter().step_by(7) {
+            let found = v.try_binary_search_checked(x).unwrap();
+            assert_eq!(found, v.try_binary_search(x).unwrap());
+            let by_key = v.try_binary_search_checked_by_key(x, |a| Some(*a));
+            assert_eq!(by_key, Ok(found));
+        }
+        v.swap(49, 50);
+        let err = v.try_binary_search_checked(&v[49]).unwrap_err();
+        assert!(err.is_unsorted());
+        assert_eq!(err.indices(), Some((49, 50)));
+    }
+
+    #[test]
+    fn try_binary_search_many() {
+        let rng = thread_rng();
+        let mut v: Vec<f64> = Standard.sample_iter(rng).take(1000).collect();
+        v.try_sort().unwrap();
+        let mut queries: Vec<f64> = Standard.sample_iter(thread_rng()).take(100).collect();
+        queries.extend_from_slice(&v[100..150]);
+        let individual =
+            |qs: &[f64]| -> Vec<_> { qs.iter().map(|q| v.try_binary_search(q).unwrap()).collect() };
+        assert_eq!(
+            v.try_binary_search_many(&queries).unwrap(),
+            individual(&queries)
+        );
+        queries.try_sort().unwrap();
+        let mut out = vec![Ok(0); queries.len()];
+        v.try_binary_search_many_into(&queries, &mut out).unwrap();
+        assert_eq!(out, individual(&queries));
+
+        let err = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, f64::NAN, 8.0]
+            .try_binary_search_many(&[0.5, 7.5])
+            .unwrap_err();
+        assert_eq!(err.index(), Some(1));
+        let mut out = [Ok(0); 2];
+        let err = [0.0; 0]
+            .try_binary_search_many_into(&[1.0, f64::NAN], &mut out)
+            .unwrap_err();
+        assert_eq!(err.index(), Some(1));
+    }
+
+    #[test]
+    fn try_exponential_search() {
+        let rng = thread_rng();
+        let mut v: Vec<f32> = Standard.sample_iter(rng).take(100).collect();
+        v.try_sort().unwrap();
+        let queries: Vec<f32> = Standard.sample_iter(thread_rng()).take(20).collect();
+        for q in queries.iter().chain(&v[..20]) {
+            let expected = v.try_binary_search(q).unwrap();
+            assert_eq!(v.try_exponential_search(q), Ok(expected));
+            for hint in [0, 17, 50, 99, 200] {
+                assert_eq!(v.try_exponential_search_from(hint, q), Ok(expected));
+            }
+        }
+        assert_eq!([0.0f32; 0].try_exponential_search_from(3, &1.0), Ok(Err(0)));
+        let err = [1.0, f32::NAN, 3.0]
+            .try_exponential_search_from(2, &0.0)
+            .unwrap_err();
+        assert_eq!(err.index(), Some(1));
+    }
+
+    #[test]
+    fn try_binary_search_heterogeneous() {
+        let rng = thread_rng();
+        let mut v: Vec<(f64, usize)> = Standard.sample_iter(rng).take(100).collect();
+        v.try_sort().unwrap();
+        let words: Vec<String> = v.iter().map(|x| format!("{:.6}", x.0)).collect();
+        for (i, x) in v.iter().enumerate() {
+            assert_eq!(
+                v.try_binary_search_with(&(x.0, x.1)),
+                v.try_binary_search(x)
+            );
+            let j = words.try_binary_search_borrow(words[i].as_str());
+            assert_eq!(j.map(|j| j.map(|j| &words[j])), Ok(Ok(&words[i])));
+        }
+
+        // The key type is different from the element type.
+        #[derive(PartialEq, PartialOrd)]
+        struct Meters(f64);
+        impl PartialEq<f64> for Meters {
+            fn eq(&self, other: &f64) -> bool {
+                self.0 == *other
+            }
+        }
+        impl PartialOrd<f64> for Meters {
+            fn partial_cmp(&self, other: &f64) -> Option<core::cmp::Ordering> {
+                self.0.partial_cmp(other)
+            }
+        }
+        let raw: Vec<f64> = v.iter().map(|x| x.0).collect();
+        let meters: Vec<Meters> = raw.iter().map(|x| Meters(*x)).collect();
+        for q in raw.iter().chain(&[-1.0, 0.5, 2.0]) {
+            assert_eq!(meters.try_binary_search_with(q), raw.try_binary_search(q));
+        }
+        assert!(meters.try_binary_search_with(&f64::NAN).is_err());
+    }
+
+    #[test]
+    fn try_nearest() {
+        let rng = thread_rng();
+        let mut v: Vec<f64> = Standard.sample_iter(rng).take(100).collect();
+        v.try_sort().unwrap();
+        let queries: Vec<f64> = Standard.sample_iter(thread_rng()).take(20).collect();
+        for q in queries.iter().chain(&[-1.0, 2.0]) {
+            let dist = |i: usize| (v[i] - q).abs();
+            let i = v.try_nearest(q, NearestTie::Lower).unwrap().unwrap();
+            assert!((0..v.len()).all(|j| dist(i) <= dist(j)));
+            let range = v.try_k_nearest(q, 5, NearestTie::Lower).unwrap();
+            assert_eq!(range.len(), 5);
+            assert!(range.contains(&i));
+            let outside = (0..v.len()).filter(|j| !range.contains(j));
+            let max_inside = range.clone().map(dist).fold(0.0, f64::max);
+            assert!(outside.map(dist).all(|d| d >= max_inside));
+        }
+
+        let v = [(0, 1), (0, 3), (0, 5)];
+        assert_eq!(
+            v.try_nearest_by_key(&4, |a| Some(a.1), NearestTie::Lower),
+            Ok(Some(1))
+        );
+        assert_eq!(
+            v.try_nearest_by_key(&4, |a| Some(a.1), NearestTie::Higher),
+            Ok(Some(2))
+        );
+        assert_eq!(
+            v.try_k_nearest_by_key(&4, 10, |a| Some(a.1), NearestTie::Lower),
+            Ok(0..3)
+        );
+        assert_eq!([0.0; 0].try_nearest(&1.0, NearestTie::Lower), Ok(None));
+        let inf = f64::INFINITY;
+        assert_eq!([1.0, inf].try_nearest(&inf, NearestTie::Lower), Ok(Some(1)));
+        assert_eq!(
+            [-inf, 1.0].try_nearest(&-inf, NearestTie::Higher),
+            Ok(Some(0))
+        );
+        assert_eq!(
+            [-inf, inf].try_k_nearest(&inf, 2, NearestTie::Lower),
+            Ok(0..2)
+        );
+        assert!([1.0, f64::NAN, 3.0]
+            .try_nearest(&0.5, NearestTie::Lower)
+            .is_err());
+        let nan = f64::NAN;
+        let err = [1.0, 2.0, nan].try_k_nearest(&0.5, 3, NearestTie::Lower);
+        assert_eq!(err.unwrap_err().index(), Some(2));
+        let err = [nan, 1.0, 2.0].try_k_nearest(&3.0, 3, NearestTie::Lower);
+        assert_eq!(err.unwrap_err().index(), Some(0));
+    }
+
+    #[test]
+    fn try_range() {
+        let rng = thread_rng();
+        let mut v: Vec<u8> = Standard.sample_iter(rng).take(200).collect();
+        v.try_sort().unwrap();
+        let filtered = |f: &dyn Fn(&u8) -> bool| v.iter().copied().filter(f).collect::<Vec<_>>();
+        assert_eq!(
+            v.try_range(50..100).unwrap(),
+            filtered(&|a| (50..100).contains(a))
+        );
+        assert_eq!(
+            v.try_range(50..=100).unwrap(),
+            filtered(&|a| (50..=100).contains(a))
+        );
+        assert_eq!(v.try_range(..=100).unwrap(), filtered(&|a| *a <= 100));
+        assert_eq!(v.try_range(200..).unwrap(), filtered(&|a| *a >= 200));
+        let bounds = (core::ops::Bound::Excluded(&50), core::ops::Bound::Unbounded);
+        assert_eq!(
+            v.try_range::<u8, _>(bounds).unwrap(),
+            filtered(&|a| *a > 50)
+        );
+
+        let mut v = [(1.0, 'a'), (2.0, 'b'), (2.0, 'c'), (3.0, 'd')];
+        let found = v.try_range_mut((2.0, 'a')..(2.0, 'z')).unwrap();
+        found.iter_mut().for_each(|e| e.0 += 0.5);
+        assert_eq!(
+            v.iter().map(|e| e.0).collect::<Vec<_>>(),
+            [1.0, 2.5, 2.5, 3.0]
+        );
+        assert!(v.try_range((f64::NAN, 'a')..).is_err());
+    }
 }
@@ -0,0 +1,186 @@
+use crate::{InvalidOrderError, OrderOperation, OrderResult};
+use core::cmp::Ordering;
+use core::ops::Range;
+
+/// Integer types which [`try_partition_point_range`] can bisect.
+pub trait BisectInt: Copy + Ord {
+    /// Returns the value in `lo..hi` at the middle, rounding down, without overflow. `lo` must be less than `hi`.
+    fn midpoint(lo: Self, hi: Self) -> Self;
+    /// Returns the next value, which must exist.
+    fn successor(self) -> Self;
+}
+
+macro_rules! impl_bisect_int {
+    ($(($t:ty, $u:ty)),*) => {$(
+        impl BisectInt for $t {
+            #[inline]
+            fn midpoint(lo: Self, hi: Self) -> Self {
+                // The distance always fits in the unsigned type, even for the whole range of the signed type.
+                let half = (hi as $u).wrapping_sub(lo as $u) / 2;
+                lo.wrapping_add(half as $t)
+            }
+            #[inline]
+            fn successor(self) -> Self {
+                self + 1
+            }
+        }
+    )*};
+}
+
+impl_bisect_int!(
+    (u8, u8),
+    (u16, u16),
+    (u32, u32),
+    (u64, u64),
+    (u128, u128),
+    (usize, usize),
+    (i8, u8),
+    (i16, u16),
+    (i32, u32),
+    (i64, u64),
+    (i128, u128),
+    (isize, usize)
+);
+
+/// Same as [`try_partition_point`](`crate::TryBinarySearch::try_partition_point`), but over the integers in `range` instead of a slice.
+///
+/// `pred` must return `true` for the values before the returned one and `false` for the rest.
+/// It returns error without the index when `pred` returns [`None`].
+/// ```
+/// use try_partialord::*;
+///
+/// assert_eq!(try_partition_point_range(0..100u32, |n| Some(n * n < 2000)), Ok(45));
+/// assert_eq!(try_partition_point_range(-128..127i8, |n| Some(n < 100)), Ok(100));
+/// assert_eq!(try_partition_point_range(0..10, |_| Some(true)), Ok(10));
+/// assert!(try_partition_point_range(0..10, |n| (n < 8).then(|| true)).is_err());
+/// ```
+pub fn try_partition_point_range<I, P>(range: Range<I>, pred: P) -> OrderResult<I>
+where
+    I: BisectInt,
+    P: FnMut(I) -> Option<bool>,
+{
+    let mut pred = pred;
+    let Range {
+        start: mut lo,
+        end: mut hi,
+    } = range;
+    while lo < hi {
+        let mid = I::midpoint(lo, hi);
+        if pred(mid).ok_or(InvalidOrderError::new(OrderOperation::BinarySearch))? {
+            lo = mid.successor();
+        } else {
+            hi = mid;
+        }
+    }
+    Ok(lo)
+}
+
+/// Finds the root of `f` in `[lo, hi]` by bisection, until the interval is not wider than `tolerance`.
+///
+/// Returns [`None`] if `f(lo)` and `f(hi)` have the same sign, so there may be no root.
+/// Returns error without the index when `f` returns NaN, because it cannot be compared with zero.
+/// Returns error with [`OrderOperation::Validate`] when `tolerance` is NaN or negative,
+/// or when `lo` is greater than `hi` or either of them is NaN.
+/// The bounds may be infinite.
+/// ```
+/// use try_partialord::*;
+///
+/// let root = try_bisect_f64(0.0, 2.0, |x| x * x - 2.0, 1e-12).unwrap().unwrap();
+/// assert!((root - 2f64.sqrt()).abs() < 1e-12);
+/// assert_eq!(try_bisect_f64(0.0, 1.0, |x| x + 1.0, 1e-12), Ok(None));
+/// assert!(try_bisect_f64(-1.0, 1.0, |x| x.ln(), 1e-12).is_err());
+/// assert_eq!(try_bisect_f64(f64::NEG_INFINITY, f64::INFINITY, |x| x - 1.0, 0.0), Ok(Some(1.0)));
+/// ```
+pub fn try_bisect_f64<F>(lo: f64, hi: f64, f: F, tolerance: f64) -> OrderResult<Option<f64>>
+where
+    F: FnMut(f64) -> f64,
+{
+    let invalid_bounds = lo.is_nan() || hi.is_nan() || lo > hi;
+    if invalid_bounds || tolerance.is_nan() || tolerance < 0.0 {
+        return Err(InvalidOrderError::new(OrderOperation::Validate));
+    }
+    let mut f = f;
+    let mut sign = |x: f64| {
+        f(x).partial_cmp(&0.0)
+            .ok_or(InvalidOrderError::new(OrderOperation::BinarySearch))
+    };
+    let (mut lo, mut hi) = (lo, hi);
+    let lo_sign = sign(lo)?;
+    if lo_sign == Ordering::Equal {
+        return Ok(Some(lo));
+    }
+    match sign(hi)? {
+        Ordering::Equal => return Ok(Some(hi)),
+        s if s == lo_sign => return Ok(None),
+        _ => {}
+    }
+    while hi - lo > tolerance {
+        let mid = midpoint(lo, hi);
+        // No float is left between `lo` and `hi`.
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        match sign(mid)? {
+            Ordering::Equal => return Ok(Some(mid)),
+            s if s == lo_sign => lo = mid,
+            _ => hi = mid,
+        }
+    }
+    Ok(Some(midpoint(lo, hi)))
+}
+
+/// Returns the value between `lo` and `hi` without overflow, taking the infinite bounds as the largest finite ones.
+fn midpoint(lo: f64, hi: f64) -> f64 {
+    let finite = |x: f64| x.clamp(f64::MIN, f64::MAX);
+    finite(lo) / 2.0 + finite(hi) / 2.0
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use crate::*;
+    use rand::distributions::Standard;
+    use rand::prelude::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn try_partition_point_range_ok() {
+        let rng = thread_rng();
+        let mut v: Vec<u16> = Standard.sample_iter(rng).take(100).collect();
+        v.try_sort().unwrap();
+        for x in &v {
+            let expected = v.partition_point(|a| a < x);
+            let found = try_partition_point_range(0..v.len() as i64, |i| Some(v[i as usize] < *x));
+            assert_eq!(found, Ok(expected as i64));
+        }
+        assert_eq!(
+            try_partition_point_range(i128::MIN..i128::MAX, |n| Some(n < -5)),
+            Ok(-5)
+        );
+        assert_eq!(try_partition_point_range(5..5usize, |_| None), Ok(5));
+    }
+
+    #[test]
+    fn try_bisect_f64_ok() {
+        let root = try_bisect_f64(3.0, 4.0, f64::sin, 0.0).unwrap().unwrap();
+        assert!((root - core::f64::consts::PI).abs() < 1e-15);
+        let root = try_bisect_f64(0.0, 10.0, |x| 5.0 - x, 1e-9)
+            .unwrap()
+            .unwrap();
+        assert!((root - 5.0).abs() < 1e-9);
+        assert_eq!(try_bisect_f64(0.0, 1.0, |x| x, 1e-9), Ok(Some(0.0)));
+        assert!(try_bisect_f64(0.0, 1.0, |_| f64::NAN, 1e-9).is_err());
+        let root = try_bisect_f64(f64::NEG_INFINITY, 5.0, |x| x + 3.0, 0.0);
+        assert_eq!(root, Ok(Some(-3.0)));
+        let root = try_bisect_f64(-f64::MAX, f64::MAX, |x| x - 1e300, 0.0);
+        assert_eq!(root, Ok(Some(1e300)));
+        for tolerance in [f64::NAN, -1.0] {
+            let err = try_bisect_f64(0.0, 1.0, |x| x - 0.5, tolerance).unwrap_err();
+            assert_eq!(err.operation(), OrderOperation::Validate);
+        }
+        for (lo, hi) in [(2.0, 0.0), (f64::NAN, 1.0), (0.0, f64::NAN)] {
+            let err = try_bisect_f64(lo, hi, |x| x * x - 2.0, 1e-12).unwrap_err();
+            assert_eq!(err.operation(), OrderOperation::Validate);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::binary_search::check_edges;
+use crate::{InvalidOrderError, OrderOperation, OrderResult, SortableFloat, TryBinarySearch};
+use std::vec::Vec;
+
+/// Where [`try_digitize`] and [`try_histogram`] put NaN values.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub enum NanBin {
+    /// Returns [`InvalidOrderError`] with the index of the NaN value.
+    #[default]
+    Error,
+    /// Puts NaN into the dedicated bin `edges.len() + 1`, after all other bins.
+    Separate,
+}
+
+/// Returns the bin of each of `values`, the same as `numpy.digitize`.
+///
+/// The bin `i` has the values in `edges[i - 1] <= x < edges[i]`, so the values before the first edge are in the bin `0`
+/// and the values after the last edge are in the bin `edges.len()`.
+/// `edges` must be sorted without NaN, otherwise it returns error, with [`is_unsorted`](`InvalidOrderError::is_unsorted`) if it is not sorted.
+///
+/// The errors about `edges` have [`OrderOperation::IsSorted`] with the positions in `edges`,
+/// and the errors about `values` have [`OrderOperation::BinarySearch`] with the position in `values`.
+/// ```
+/// use try_partialord::*;
+///
+/// let edges = [0.0, 1.0, 2.0];
+/// let values = [0.5, -1.0, 2.0, f64::NAN, 1.0];
+/// assert!(try_digitize(&values, &edges, NanBin::Error).is_err());
+/// assert_eq!(try_digitize(&values, &edges, NanBin::Separate), Ok(vec![1, 0, 3, 4, 2]));
+/// assert_eq!(try_histogram(&values, &edges, NanBin::Separate), Ok(vec![1, 1, 1, 1, 1]));
+/// ```
+pub fn try_digitize<T: SortableFloat>(
+    values: &[T],
+    edges: &[T],
+    nan: NanBin,
+) -> OrderResult<Vec<usize>> {
+    check_edges(edges)?;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, x)| bin(edges, i, x, nan))
+        .collect()
+}
+
+/// Counts the values in each bin of [`try_digitize`].
+///
+/// The result has `edges.len() + 1` bins, and one more for NaN with [`NanBin::Separate`].
+pub fn try_histogram<T: SortableFloat>(
+    values: &[T],
+    edges: &[T],
+    nan: NanBin,
+) -> OrderResult<Vec<usize>> {
+    check_edges(edges)?;
+    let bins = match nan {
+        NanBin::Error => edges.len() + 1,
+        NanBin::Separate => edges.len() + 2,
+    };
+    let mut counts = vec![0; bins];
+    for (i, x) in values.iter().enumerate() {
+        counts[bin(edges, i, x, nan)?] += 1;
+    }
+    Ok(counts)
+}
+
+fn bin<T: SortableFloat>(edges: &[T], i: usize, x: &T, nan: NanBin) -> OrderResult<usize> {
+    if x.is_nan() {
+        match nan {
+            NanBin::Error => Err(InvalidOrderError::with_index(
+                OrderOperation::BinarySearch,
+                i,
+            )),
+            NanBin::Separate => Ok(edges.len() + 1),
+        }
+    } else {
+        edges
+            .try_upper_bound(x)
+            .map_err(|_| InvalidOrderError::with_index(OrderOperation::BinarySearch, i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::distributions::Standard;
+    use rand::prelude::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn try_histogram_ok() {
+        let rng = thread_rng();
+        let values: Vec<f64> = Standard.sample_iter(rng).take(1000).collect();
+        let edges = [0.25, 0.5, 0.75];
+        let counts = try_histogram(&values, &edges, NanBin::Error).unwrap();
+        assert_eq!(counts.iter().sum::<usize>(), 1000);
+        let expected: Vec<_> = [0.0, 0.25, 0.5, 0.75]
+            .iter()
+            .map(|lo| {
+                values
+                    .iter()
+                    .filter(|x| *lo <= **x && **x < lo + 0.25)
+                    .count()
+            })
+            .collect();
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn try_digitize_edges() {
+        let values = [0.5, 1.0];
+        let err = try_digitize(&values, &[1.0, 0.0], NanBin::Error).unwrap_err();
+        assert!(err.is_unsorted());
+        let err = try_histogram(&values, &[0.0, f64::NAN], NanBin::Separate).unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::IsSorted);
+        assert_eq!(err.index(), Some(1));
+        assert_eq!(try_digitize(&values, &[], NanBin::Error), Ok(vec![0, 0]));
+        let values = [1.0, f32::NAN];
+        let err = try_digitize(&values, &[0.0], NanBin::Error).unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::BinarySearch);
+        assert_eq!(err.index(), Some(1));
+        let empty: [f64; 0] = [];
+        assert!(empty.try_searchsorted(&[f64::NAN], Side::Left).is_err());
+        let err = [1.0, 2.0, 3.0, f64::NAN]
+            .try_searchsorted(&[0.5], Side::Left)
+            .unwrap_err();
+        assert_eq!(err.index(), Some(3));
+    }
+}
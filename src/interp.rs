@@ -0,0 +1,207 @@
+use crate::{InvalidOrderError, OrderOperation, OrderResult, TryBinarySearch, TrySort};
+use core::cmp::Ordering;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// How [`try_interp`] evaluates the queries outside of `x`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub enum Extrapolate {
+    /// Returns the value at the nearest end, the same as `numpy.interp`.
+    #[default]
+    Clamp,
+    /// Returns error with [`is_out_of_range`](`InvalidOrderError::is_out_of_range`).
+    Error,
+    /// Extends the first or the last segment.
+    Linear,
+}
+
+/// Options for [`try_interp`] and [`try_interp_many`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct InterpOptions {
+    /// Extrapolation for the queries less than `x[0]`.
+    pub left: Extrapolate,
+    /// Extrapolation for the queries greater than the last of `x`.
+    pub right: Extrapolate,
+    /// Checks that the whole `x` is sorted in `O(n)` before searching, which is always done with `debug_assertions`.
+    pub checked: bool,
+}
+
+/// Evaluates the piecewise-linear function through the points `(x[i], y[i])` at `query`, the same as `numpy.interp`.
+///
+/// `x` must be sorted. If it is not, the result is unspecified, but with [`checked`](`InterpOptions::checked`)
+/// or `debug_assertions` it returns error with [`is_unsorted`](`InvalidOrderError::is_unsorted`).
+///
+/// The errors are:
+/// - [`OrderOperation::Interp`] with the positions in `x`, if `x` is not sorted or the probed `x` is NaN.
+/// - [`OrderOperation::Interp`] with [`is_out_of_range`](`InvalidOrderError::is_out_of_range`) and the position of the query,
+///   if the query is outside of `x` with [`Extrapolate::Error`].
+/// - [`OrderOperation::Validate`] with the position of the query, if the query is NaN.
+/// - [`OrderOperation::Validate`] without the index, if `x` is empty or `x` and `y` have different lengths.
+/// ```
+/// use try_partialord::*;
+///
+/// let x = [0.0, 1.0, 3.0];
+/// let y = [0.0, 10.0, 30.0];
+/// let clamp = InterpOptions::default();
+/// assert_eq!(try_interp(&x, &y, 2.0, clamp), Ok(20.0));
+/// assert_eq!(try_interp(&x, &y, 4.0, clamp), Ok(30.0));
+/// assert!(try_interp(&x, &y, f64::NAN, clamp).is_err());
+///
+/// let options = InterpOptions { left: Extrapolate::Error, right: Extrapolate::Linear, ..clamp };
+/// assert_eq!(try_interp(&x, &y, 4.0, options), Ok(40.0));
+/// assert!(try_interp(&x, &y, -1.0, options).unwrap_err().is_out_of_range());
+/// assert!(try_interp(&[1.0, 0.0], &y[..2], 0.5, InterpOptions { checked: true, ..clamp }).unwrap_err().is_unsorted());
+/// ```
+pub fn try_interp(x: &[f64], y: &[f64], query: f64, options: InterpOptions) -> OrderResult<f64> {
+    check_points(x, y, options)?;
+    interp_at(x, y, 0, query, options)
+}
+
+/// Same as [`try_interp`] for each of `queries`.
+///
+/// `x` is checked only once. For the queries out of range, the index of the error is the position in `queries`.
+#[cfg(feature = "std")]
+pub fn try_interp_many(
+    x: &[f64],
+    y: &[f64],
+    queries: &[f64],
+    options: InterpOptions,
+) -> OrderResult<Vec<f64>> {
+    check_points(x, y, options)?;
+    queries
+        .iter()
+        .enumerate()
+        .map(|(i, q)| interp_at(x, y, i, *q, options))
+        .collect()
+}
+
+fn check_points(x: &[f64], y: &[f64], options: InterpOptions) -> OrderResult<()> {
+    if x.is_empty() || x.len() != y.len() {
+        return Err(InvalidOrderError::new(OrderOperation::Validate));
+    }
+    if !(options.checked || cfg!(debug_assertions)) {
+        return Ok(());
+    }
+    if !x
+        .try_is_sorted_by_partial_cmp(|a, b| a.partial_cmp(b))
+        .map_err(|e| e.with_operation(OrderOperation::Interp))?
+    {
+        let i = x.windows(2).position(|w| w[0] > w[1]).unwrap_or(0);
+        return Err(InvalidOrderError::unsorted(
+            OrderOperation::Interp,
+            i,
+            i + 1,
+        ));
+    }
+    Ok(())
+}
+
+/// Evaluates at `query`, which is the `index`-th query for the error.
+fn interp_at(
+    x: &[f64],
+    y: &[f64],
+    index: usize,
+    query: f64,
+    options: InterpOptions,
+) -> OrderResult<f64> {
+    if query.is_nan() {
+        return Err(InvalidOrderError::with_index(
+            OrderOperation::Validate,
+            index,
+        ));
+    }
+    let last = x.len() - 1;
+    // The number of the elements of `x` not greater than `query`.
+    let i = x
+        .try_partition_point(|a| a.partial_cmp(&query).map(Ordering::is_le))
+        .map_err(|e| e.with_operation(OrderOperation::Interp))?;
+    let (extrapolate, end, segment) = match i {
+        0 => (options.left, 0, 0),
+        i if i > last => {
+            if x[last] == query {
+                return Ok(y[last]);
+            }
+            (options.right, last, last.saturating_sub(1))
+        }
+        i => return Ok(lerp(x, y, i - 1, query)),
+    };
+    match extrapolate {
+        Extrapolate::Clamp => Ok(y[end]),
+        Extrapolate::Error => Err(InvalidOrderError::out_of_range(
+            OrderOperation::Interp,
+            index,
+        )),
+        Extrapolate::Linear => Ok(lerp(x, y, segment, query)),
+    }
+}
+
+/// Evaluates the line through the points `i` and `i + 1` at `query`, or `y[i]` if there is no such line.
+fn lerp(x: &[f64], y: &[f64], i: usize, query: f64) -> f64 {
+    match (x.get(i + 1), y.get(i + 1)) {
+        (Some(x1), Some(y1)) if *x1 != x[i] => y[i] + (query - x[i]) / (x1 - x[i]) * (y1 - y[i]),
+        _ => y[i],
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use crate::*;
+    use rand::distributions::Standard;
+    use rand::prelude::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn try_interp_ok() {
+        let rng = thread_rng();
+        let mut x: Vec<f64> = Standard.sample_iter(rng).take(100).collect();
+        x.try_sort().unwrap();
+        let y: Vec<f64> = x.iter().map(|a| 3.0 * a - 1.0).collect();
+        let queries: Vec<f64> = (0..=20).map(|i| i as f64 / 10.0 - 0.5).collect();
+        let options = InterpOptions {
+            left: Extrapolate::Linear,
+            right: Extrapolate::Linear,
+            checked: true,
+        };
+        let values = try_interp_many(&x, &y, &queries, options).unwrap();
+        for (q, v) in queries.iter().zip(&values) {
+            assert!((3.0 * q - 1.0 - v).abs() < 1e-6);
+        }
+        let values = try_interp_many(&x, &y, &queries, InterpOptions::default()).unwrap();
+        for (q, v) in queries.iter().zip(&values) {
+            let q = q.max(x[0]).min(x[99]);
+            assert!((3.0 * q - 1.0 - v).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn try_interp_error() {
+        let x = [0.0, 1.0, 1.0, 2.0];
+        let y = [0.0, 1.0, 3.0, 4.0];
+        let options = InterpOptions {
+            left: Extrapolate::Error,
+            right: Extrapolate::Error,
+            checked: true,
+        };
+        assert_eq!(try_interp(&x, &y, 1.0, options), Ok(3.0));
+        assert_eq!(try_interp(&x, &y, 2.0, options), Ok(4.0));
+        let err = try_interp_many(&x, &y, &[0.5, 2.5], options).unwrap_err();
+        assert!(err.is_out_of_range());
+        assert_eq!(err.index(), Some(1));
+        let err = try_interp(&[1.0, f64::NAN], &y[..2], 0.5, options).unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::Interp);
+        let err = try_interp_many(&x, &y, &[0.5, f64::NAN], options).unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::Validate);
+        assert_eq!(err.index(), Some(1));
+        let err = try_interp(&x, &y[..3], 0.5, options).unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::Validate);
+        assert_eq!(err.index(), None);
+        assert!(try_interp(&[], &[], 0.5, options).is_err());
+        let err = try_interp(&[0.0, 2.0, 1.0], &y[..3], 0.5, options).unwrap_err();
+        assert_eq!(err.indices(), Some((1, 2)));
+        assert_eq!(
+            try_interp(&[1.0], &[5.0], 2.0, InterpOptions::default()),
+            Ok(5.0)
+        );
+    }
+}
@@ -35,6 +35,9 @@
 //!
 //! This is safer than using something like `sort_by` with ignoreing None case of [`partial_cmp`](`std::cmp::PartialOrd::partial_cmp`) because it handle error instead of panic.
 //!
+//! Methods ending with `_by_result` take a comparator returning [`Result`] with your own error type instead of [`Option`],
+//! and return that error as is.
+//!
 //! Sort is using the same logic as std.
 //!
 //! This supports `no_std` with no `std` feature flag.
@@ -59,21 +62,224 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "std")]
+mod binary_heap;
 mod binary_search;
+mod bisect;
+#[cfg(feature = "std")]
+mod histogram;
+mod interp;
 mod min_max;
+mod not_nan;
+mod quantile;
+#[cfg(feature = "std")]
+mod rank;
 mod sort;
-pub use binary_search::TryBinarySearch;
+mod sorted;
+#[cfg(feature = "std")]
+mod sorted_map;
+#[cfg(feature = "std")]
+mod sorted_vec;
+#[cfg(feature = "std")]
+pub use binary_heap::TryBinaryHeap;
+pub use binary_search::{NearestTie, Side, TryBinarySearch};
+pub use bisect::{try_bisect_f64, try_partition_point_range, BisectInt};
 use core::fmt::{Display, Error, Formatter};
+#[cfg(feature = "std")]
+pub use histogram::{try_digitize, try_histogram, NanBin};
+#[cfg(feature = "std")]
+pub use interp::try_interp_many;
+pub use interp::{try_interp, Extrapolate, InterpOptions};
 pub use min_max::TryMinMax;
+pub use not_nan::*;
+pub use quantile::{DiscreteQuantile, Interpolate, QuantileMethod, TryQuantile};
+#[cfg(feature = "std")]
+pub use rank::{RankMethod, TryRank};
 pub use sort::*;
+pub use sorted::{Sorted, SortedStorage};
+#[cfg(feature = "std")]
+pub use sorted_map::TrySortedMap;
+#[cfg(feature = "std")]
+pub use sorted_vec::TrySortedVec;
 
 /// Error when [`partial_cmp`](`std::cmp::PartialOrd::partial_cmp`) returns [`None`] during the operation.
+///
+/// It tells which operation failed and, when known, the positions of the elements which could not be compared.
+/// ```
+/// use try_partialord::*;
+///
+/// let mut v = vec![1.0, 3.0, f64::NAN, 2.0];
+/// let err = v.try_is_sorted().unwrap_err();
+/// assert_eq!(err.operation(), OrderOperation::IsSorted);
+/// assert_eq!(err.indices(), Some((1, 2)));
+///
+/// let err = v.try_sort().unwrap_err();
+/// assert_eq!(err.operation(), OrderOperation::Sort);
+/// let (i, j) = err.indices().unwrap();
+/// assert!(v[i].is_nan() || v[j].is_nan());
+/// ```
 #[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Hash, Default, Debug)]
-pub struct InvalidOrderError;
+pub struct InvalidOrderError {
+    operation: OrderOperation,
+    index: Option<usize>,
+    other: Option<usize>,
+    unsorted: bool,
+    out_of_range: bool,
+}
+
+impl InvalidOrderError {
+    /// Creates an error for `operation` without position information.
+    pub const fn new(operation: OrderOperation) -> Self {
+        InvalidOrderError {
+            operation,
+            index: None,
+            other: None,
+            unsorted: false,
+            out_of_range: false,
+        }
+    }
+
+    pub(crate) const fn with_index(operation: OrderOperation, index: usize) -> Self {
+        InvalidOrderError {
+            operation,
+            index: Some(index),
+            other: None,
+            unsorted: false,
+            out_of_range: false,
+        }
+    }
+
+    pub(crate) const fn with_indices(
+        operation: OrderOperation,
+        index: usize,
+        other: usize,
+    ) -> Self {
+        InvalidOrderError {
+            operation,
+            index: Some(index),
+            other: Some(other),
+            unsorted: false,
+            out_of_range: false,
+        }
+    }
+
+    /// Error when the elements at `index` and `other` can be compared, but they are in the wrong order.
+    pub(crate) const fn unsorted(operation: OrderOperation, index: usize, other: usize) -> Self {
+        InvalidOrderError {
+            operation,
+            index: Some(index),
+            other: Some(other),
+            unsorted: true,
+            out_of_range: false,
+        }
+    }
+
+    /// Error when the value at `index` can be compared, but it is outside of the allowed range.
+    pub(crate) const fn out_of_range(operation: OrderOperation, index: usize) -> Self {
+        InvalidOrderError {
+            operation,
+            index: Some(index),
+            other: None,
+            unsorted: false,
+            out_of_range: true,
+        }
+    }
+
+    /// Same error reported as `operation`, for the methods built on other operations.
+    pub(crate) fn with_operation(self, operation: OrderOperation) -> Self {
+        InvalidOrderError { operation, ..self }
+    }
+
+    /// Operation which produced this error.
+    pub const fn operation(&self) -> OrderOperation {
+        self.operation
+    }
+
+    /// Index of an element which could not be compared, if known.
+    ///
+    /// For slices this is the position in the slice when the error is returned, for iterators the position in the iteration.
+    /// For binary search this is the position of the probed element which could not be compared with the target.
+    pub const fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    /// Indices of both elements which could not be compared with each other, if known.
+    pub fn indices(&self) -> Option<(usize, usize)> {
+        Some((self.index?, self.other?))
+    }
+
+    /// Returns `true` if the elements could be compared but the slice was found not to be sorted,
+    /// like in [`try_binary_search_checked`](`TryBinarySearch::try_binary_search_checked`).
+    /// Then [`indices`](`InvalidOrderError::indices`) are the elements in the wrong order.
+    pub const fn is_unsorted(&self) -> bool {
+        self.unsorted
+    }
+
+    /// Returns `true` if the value was valid but outside of the allowed range,
+    /// like the query of [`try_interp`] with [`Extrapolate::Error`] or the quantile not in `0.0..=1.0`.
+    /// Then [`index`](`InvalidOrderError::index`) is the position of the value.
+    pub const fn is_out_of_range(&self) -> bool {
+        self.out_of_range
+    }
+}
 
 impl Display for InvalidOrderError {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
-        fmt.write_str("Failed because partial_cmp returns None.")
+        if self.unsorted {
+            fmt.write_str("Failed because the input is not sorted")?;
+        } else if self.out_of_range {
+            fmt.write_str("Failed because the value is out of range")?;
+        } else if self.operation == OrderOperation::Validate {
+            fmt.write_str("Failed because of invalid value")?;
+        } else {
+            fmt.write_str("Failed because partial_cmp returns None")?;
+        }
+        match (self.index, self.other) {
+            (Some(i), Some(j)) => write!(fmt, " in {} at index {} and {}.", self.operation, i, j),
+            (Some(i), None) => write!(fmt, " in {} at index {}.", self.operation, i),
+            _ => write!(fmt, " in {}.", self.operation),
+        }
+    }
+}
+
+/// Operation which produced an [`InvalidOrderError`].
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Hash, Default, Debug)]
+#[non_exhaustive]
+pub enum OrderOperation {
+    /// Unknown operation.
+    #[default]
+    Unknown,
+    /// Sorting, like [`TrySort::try_sort`].
+    Sort,
+    /// Selection, like [`TrySort::try_select_nth_unstable`].
+    Select,
+    /// Getting min or max, like [`TryMinMax::try_min`].
+    MinMax,
+    /// Binary search, like [`TryBinarySearch::try_binary_search`].
+    BinarySearch,
+    /// Checking order, like [`TrySort::try_is_sorted`].
+    IsSorted,
+    /// Priority queue operations, like [`TryBinaryHeap::try_push`].
+    Heap,
+    /// Checking values for a wrapper, like [`try_as_not_nan`]. The index is the first invalid value.
+    Validate,
+    /// Interpolation, like [`try_interp`].
+    Interp,
+}
+
+impl Display for OrderOperation {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+        fmt.write_str(match self {
+            OrderOperation::Unknown => "unknown operation",
+            OrderOperation::Sort => "sort",
+            OrderOperation::Select => "select",
+            OrderOperation::MinMax => "min_max",
+            OrderOperation::BinarySearch => "binary_search",
+            OrderOperation::IsSorted => "is_sorted",
+            OrderOperation::Heap => "heap",
+            OrderOperation::Validate => "validate",
+            OrderOperation::Interp => "interp",
+        })
     }
 }
 
@@ -83,6 +289,23 @@ impl std::error::Error for InvalidOrderError {}
 /// Alias for result
 pub type OrderResult<T> = Result<T, InvalidOrderError>;
 
+/// Returns `error` if `compare` cannot compare `x` with itself.
+///
+/// Containers check this for each new element, because a lone element may never be compared with another one.
+pub(crate) fn check_comparable<T, R, F>(
+    x: &T,
+    compare: F,
+    error: InvalidOrderError,
+) -> OrderResult<()>
+where
+    F: FnOnce(&T, &T) -> Option<R>,
+{
+    match compare(x, x) {
+        Some(_) => Ok(()),
+        None => Err(error),
+    }
+}
+
 fn ord_as_cmp<T>(a: &T, b: &T) -> Option<bool>
 where
     T: PartialOrd<T>,
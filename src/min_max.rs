@@ -1,5 +1,9 @@
-use crate::{InvalidOrderError, OrderResult};
+#[cfg(feature = "std")]
+use crate::sort::std_quicksort;
+use crate::{InvalidOrderError, OrderOperation, OrderResult};
 use core::cmp::Ordering;
+#[cfg(feature = "std")]
+use std::vec::Vec;
 
 /// Min and max methods for [`PartialOrd`]
 /// ```
@@ -76,12 +80,175 @@ pub trait TryMinMax<T> {
         let mut fk = f;
         self.try_select_by(|a, b| fk(a).partial_cmp(&fk(b)), Ordering::Less)
     }
+    /// Version of [`Iterator::min_by`] with the comparator which can fail with your own error.
+    ///
+    /// The error returned by `compare` is returned as is.
+    #[inline]
+    fn try_min_by_result<E, F>(self, compare: F) -> Result<Option<T>, E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>,
+        Self: Sized,
+    {
+        self.try_select_by_result(compare, Ordering::Greater)
+    }
+    /// Version of [`Iterator::max_by`] with the comparator which can fail with your own error.
+    ///
+    /// The error returned by `compare` is returned as is.
+    #[inline]
+    fn try_max_by_result<E, F>(self, compare: F) -> Result<Option<T>, E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>,
+        Self: Sized,
+    {
+        self.try_select_by_result(compare, Ordering::Less)
+    }
+    /// Returns the smallest `k` elements in ascending order, keeping only `k` elements in memory.
+    ///
+    /// Equal elements are in the order of the iteration, the same as the first `k` elements after stable sort.
+    /// ```
+    /// use try_partialord::*;
+    ///
+    /// let v = [3.0, 1.0, 4.0, 1.5, 5.0];
+    /// assert_eq!(v.iter().copied().try_k_smallest(2), Ok(vec![1.0, 1.5]));
+    /// assert_eq!(v.iter().copied().try_k_largest(2), Ok(vec![5.0, 4.0]));
+    /// assert!(v.iter().chain(&[f64::NAN]).try_k_smallest(2).is_err());
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_k_smallest(self, k: usize) -> OrderResult<Vec<T>>
+    where
+        T: PartialOrd<T>,
+        Self: Sized,
+    {
+        self.try_k_select_by(k, |a, b| a.partial_cmp(b), Ordering::Greater)
+    }
+    /// Returns the smallest `k` elements in ascending order with the comparator.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_k_smallest_by<F>(self, k: usize, compare: F) -> OrderResult<Vec<T>>
+    where
+        F: FnMut(&T, &T) -> Option<Ordering>,
+        Self: Sized,
+    {
+        self.try_k_select_by(k, compare, Ordering::Greater)
+    }
+    /// Returns the smallest `k` elements in ascending order with the key extraction function.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_k_smallest_by_key<K, F>(self, k: usize, f: F) -> OrderResult<Vec<T>>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>,
+        Self: Sized,
+    {
+        let mut fk = f;
+        self.try_k_select_by(k, |a, b| fk(a).partial_cmp(&fk(b)), Ordering::Greater)
+    }
+    /// Returns the largest `k` elements in descending order, keeping only `k` elements in memory.
+    ///
+    /// Equal elements are in the order of the iteration.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_k_largest(self, k: usize) -> OrderResult<Vec<T>>
+    where
+        T: PartialOrd<T>,
+        Self: Sized,
+    {
+        self.try_k_select_by(k, |a, b| a.partial_cmp(b), Ordering::Less)
+    }
+    /// Returns the largest `k` elements in descending order with the comparator.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_k_largest_by<F>(self, k: usize, compare: F) -> OrderResult<Vec<T>>
+    where
+        F: FnMut(&T, &T) -> Option<Ordering>,
+        Self: Sized,
+    {
+        self.try_k_select_by(k, compare, Ordering::Less)
+    }
+    /// Returns the largest `k` elements in descending order with the key extraction function.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_k_largest_by_key<K, F>(self, k: usize, f: F) -> OrderResult<Vec<T>>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>,
+        Self: Sized,
+    {
+        let mut fk = f;
+        self.try_k_select_by(k, |a, b| fk(a).partial_cmp(&fk(b)), Ordering::Less)
+    }
+    /// Version of [`try_k_smallest_by`](`TryMinMax::try_k_smallest_by`) with the comparator which can fail with your own error.
+    ///
+    /// The error returned by `compare` is returned as is.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_k_smallest_by_result<E, F>(self, k: usize, compare: F) -> Result<Vec<T>, E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>,
+        Self: Sized,
+    {
+        self.try_k_select_by_result(k, compare, Ordering::Greater)
+    }
+    /// Version of [`try_k_largest_by`](`TryMinMax::try_k_largest_by`) with the comparator which can fail with your own error.
+    ///
+    /// The error returned by `compare` is returned as is.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_k_largest_by_result<E, F>(self, k: usize, compare: F) -> Result<Vec<T>, E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>,
+        Self: Sized,
+    {
+        self.try_k_select_by_result(k, compare, Ordering::Less)
+    }
     /// Base method for getting min or max. `target` is to tell what you want to get is min or max.
     /// - min -> [`Ordering::Greater`]
     /// - max -> [`Ordering::Less`]
+    #[inline]
     fn try_select_by<F>(self, compare: F, target: Ordering) -> OrderResult<Option<T>>
+    where
+        F: FnMut(&T, &T) -> Option<Ordering>,
+        Self: Sized,
+    {
+        let mut compare = compare;
+        // Follow the candidate the same way as the selection, to report the positions on failure.
+        let (mut candidate, mut next) = (0, 1);
+        self.try_select_by_result(
+            |a, b| {
+                let ord = compare(a, b).ok_or_else(|| {
+                    InvalidOrderError::with_indices(OrderOperation::MinMax, candidate, next)
+                })?;
+                if ord == target {
+                    candidate = next;
+                }
+                next += 1;
+                Ok(ord)
+            },
+            target,
+        )
+    }
+    /// Same as [`try_select_by`](`TryMinMax::try_select_by`), but `compare` can fail with your own error.
+    ///
+    /// `compare` is called with the current candidate and the next element, in the order of the iteration.
+    fn try_select_by_result<E, F>(self, compare: F, target: Ordering) -> Result<Option<T>, E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>;
+    /// Base method for getting `k` min or max elements. `target` is the same as [`try_select_by`](`TryMinMax::try_select_by`).
+    #[cfg(feature = "std")]
+    fn try_k_select_by<F>(self, k: usize, compare: F, target: Ordering) -> OrderResult<Vec<T>>
     where
         F: FnMut(&T, &T) -> Option<Ordering>;
+    /// Same as [`try_k_select_by`](`TryMinMax::try_k_select_by`), but `compare` can fail with your own error.
+    #[cfg(feature = "std")]
+    fn try_k_select_by_result<E, F>(
+        self,
+        k: usize,
+        compare: F,
+        target: Ordering,
+    ) -> Result<Vec<T>, E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>;
 }
 
 impl<T, Iter> TryMinMax<T> for Iter
@@ -89,33 +256,95 @@ where
     Iter: IntoIterator<Item = T>,
 {
     #[inline]
-    fn try_select_by<F>(self, compare: F, target: Ordering) -> OrderResult<Option<T>>
+    fn try_select_by_result<E, F>(self, compare: F, target: Ordering) -> Result<Option<T>, E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>,
+    {
+        let mut compare = compare;
+        let mut iter = self.into_iter();
+        match iter.next() {
+            Some(first) => iter
+                .try_fold(first, |a, b| match compare(&a, &b)? {
+                    ord if ord == target => Ok(b),
+                    _ => Ok(a),
+                })
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_k_select_by<F>(self, k: usize, compare: F, target: Ordering) -> OrderResult<Vec<T>>
     where
         F: FnMut(&T, &T) -> Option<Ordering>,
     {
-        try_select_by(self.into_iter(), compare, target)
+        let mut compare = compare;
+        try_k_select_by(self.into_iter(), k, |a, b| compare(a, b).ok_or(()), target)
+            .map_err(|((), i, j)| InvalidOrderError::with_indices(OrderOperation::MinMax, i, j))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_k_select_by_result<E, F>(
+        self,
+        k: usize,
+        compare: F,
+        target: Ordering,
+    ) -> Result<Vec<T>, E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>,
+    {
+        try_k_select_by(self.into_iter(), k, compare, target).map_err(|(e, _, _)| e)
     }
 }
 
-fn try_select_by<T, F>(
-    mut iter: impl Iterator<Item = T>,
+/// Selects `k` min or max elements with a binary heap of size `k`, returning the error with the indices of the elements on failure.
+#[cfg(feature = "std")]
+fn try_k_select_by<T, E, F>(
+    iter: impl Iterator<Item = T>,
+    k: usize,
     compare: F,
     target: Ordering,
-) -> OrderResult<Option<T>>
+) -> Result<Vec<T>, (E, usize, usize)>
 where
-    F: FnMut(&T, &T) -> Option<Ordering>,
+    F: FnMut(&T, &T) -> Result<Ordering, E>,
 {
     let mut compare = compare;
-    if let Some(first) = iter.next() {
-        match iter.try_fold(first, |a, b| {
-            Some(if compare(&a, &b)? == target { b } else { a })
-        }) {
-            None => Err(InvalidOrderError),
-            x => Ok(x), //some
+    // `a` comes before `b` in the result. Ties are broken by the position in the iteration.
+    let mut is_before = |a: &(usize, T), b: &(usize, T)| match compare(&a.1, &b.1) {
+        Ok(Ordering::Equal) => Ok(a.0 < b.0),
+        Ok(ord) => Ok(ord != target),
+        Err(e) => Err((e, a.0, b.0)),
+    };
+
+    let mut iter = iter.enumerate();
+    let mut heap: Vec<(usize, T)> = iter.by_ref().take(k).collect();
+    select_into_heap(&mut heap, iter, &mut is_before)?;
+    Ok(heap.into_iter().map(|(_, x)| x).collect())
+}
+
+/// Keeps in `heap` the elements which come first among `heap` and `iter`, then sorts `heap`.
+#[cfg(feature = "std")]
+fn select_into_heap<T, E, F>(
+    heap: &mut [T],
+    iter: impl Iterator<Item = T>,
+    is_before: &mut F,
+) -> Result<(), E>
+where
+    F: FnMut(&T, &T) -> Result<bool, E>,
+{
+    // The top of the heap is the element which comes last in the result.
+    std_quicksort::heapify(heap, is_before)?;
+    if !heap.is_empty() {
+        for item in iter {
+            if is_before(&item, &heap[0])? {
+                heap[0] = item;
+                std_quicksort::sift_down(heap, 0, is_before)?;
+            }
         }
-    } else {
-        Ok(None)
     }
+    std_quicksort::quicksort(heap, is_before)
 }
 
 #[cfg(test)]
@@ -133,10 +362,7 @@ mod tests {
         let min = v.iter().try_min().unwrap();
         assert_eq!(min, v.iter().min_by(|a, b| a.partial_cmp(b).unwrap()));
         let min = v.iter().try_min().unwrap();
-        assert_eq!(
-            min.as_deref(),
-            v.iter().min_by(|a, b| a.partial_cmp(b).unwrap())
-        );
+        assert_eq!(min, v.iter().min_by(|a, b| a.partial_cmp(b).unwrap()));
 
         let iter = &mut v.iter();
         let min = iter.try_min().unwrap();
@@ -151,4 +377,58 @@ mod tests {
         let min = v.iter().try_min();
         assert!(min.is_err());
     }
+
+    #[test]
+    fn try_max_error_index() {
+        let v = [1.0, 3.0, 2.0, f32::NAN, 0.0];
+        let err = v.iter().try_max().unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::MinMax);
+        assert_eq!(err.indices(), Some((1, 3)));
+    }
+
+    #[test]
+    fn try_max_by_result() {
+        let v = ["1", "3", "2"];
+        let parse = |a: &&str, b: &&str| {
+            Ok::<_, core::num::ParseIntError>(a.parse::<u32>()?.cmp(&b.parse()?))
+        };
+        assert_eq!(v.iter().copied().try_max_by_result(parse), Ok(Some("3")));
+        let v = ["1", "x", "2"];
+        assert!(v.iter().copied().try_min_by_result(parse).is_err());
+        assert!(v
+            .iter()
+            .copied()
+            .try_k_smallest_by_result(2, parse)
+            .is_err());
+        let v = ["4", "1", "3", "2"];
+        let k = v.iter().copied().try_k_largest_by_result(2, parse);
+        assert_eq!(k, Ok(vec!["4", "3"]));
+    }
+
+    #[test]
+    fn try_k_smallest() {
+        let rng = thread_rng();
+        let mut v: Vec<f32> = Standard.sample_iter(rng).take(1000).collect();
+        let mut sorted = v.clone();
+        sorted.try_sort().unwrap();
+        assert_eq!(v.iter().copied().try_k_smallest(10).unwrap(), &sorted[..10]);
+        assert_eq!(v.iter().copied().try_k_smallest(0).unwrap(), &[]);
+        assert_eq!(v.iter().copied().try_k_smallest(2000).unwrap(), sorted);
+        sorted.reverse();
+        assert_eq!(v.iter().copied().try_k_largest(10).unwrap(), &sorted[..10]);
+
+        v.insert(500, f32::NAN);
+        let err = v.iter().try_k_largest(10).unwrap_err();
+        let (i, j) = err.indices().unwrap();
+        assert!(v[i].is_nan() || v[j].is_nan());
+    }
+
+    #[test]
+    fn try_k_smallest_by_key_ties() {
+        let v = [(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd'), (2, 'e')];
+        let k = v.iter().try_k_smallest_by_key(3, |x| Some(x.0)).unwrap();
+        assert_eq!(k, [&(0, 'b'), &(0, 'd'), &(1, 'a')]);
+        let k = v.iter().try_k_largest_by_key(2, |x| Some(x.0)).unwrap();
+        assert_eq!(k, [&(2, 'e'), &(1, 'a')]);
+    }
 }
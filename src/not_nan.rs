@@ -0,0 +1,189 @@
+use crate::{InvalidOrderError, OrderOperation, OrderResult, SortableFloat};
+use core::cmp::Ordering;
+use core::fmt::{Display, Error, Formatter};
+
+macro_rules! float_wrapper {
+    ($(#[$doc:meta])* $name:ident, $is_valid:ident, $invalid:literal, $as:ident, $as_mut:ident) => {
+        $(#[$doc])*
+        #[derive(Copy, Clone, Default, Debug)]
+        #[repr(transparent)]
+        pub struct $name<T>(T);
+
+        impl<T: SortableFloat> $name<T> {
+            #[doc = concat!("Wraps `value`, or returns [`None`] if it is ", $invalid, ".")]
+            #[inline]
+            pub fn new(value: T) -> Option<Self> {
+                if $is_valid(value) {
+                    Some($name(value))
+                } else {
+                    None
+                }
+            }
+
+            /// Returns the wrapped value.
+            #[inline]
+            pub fn get(self) -> T {
+                self.0
+            }
+
+            /// Views the slice of wrapped values as the slice of the values.
+            #[inline]
+            pub fn as_inner_slice(v: &[Self]) -> &[T] {
+                // SAFETY: `Self` is `repr(transparent)` over `T`.
+                unsafe { core::slice::from_raw_parts(v.as_ptr() as *const T, v.len()) }
+            }
+        }
+
+        impl<T: SortableFloat> PartialEq for $name<T> {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl<T: SortableFloat> Eq for $name<T> {}
+
+        impl<T: SortableFloat> PartialOrd for $name<T> {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<T: SortableFloat> Ord for $name<T> {
+            #[inline]
+            fn cmp(&self, other: &Self) -> Ordering {
+                // The values are never NaN, so `partial_cmp` always returns `Some`.
+                self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+            }
+        }
+
+        impl<T: Display> Display for $name<T> {
+            fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), Error> {
+                self.0.fmt(fmt)
+            }
+        }
+
+        #[doc = concat!("Checks that no value in `v` is ", $invalid, " and views it as the slice of [`", stringify!($name), "`] without copying.")]
+        ///
+        /// The index in the error is the first invalid value.
+        #[inline]
+        pub fn $as<T: SortableFloat>(v: &[T]) -> OrderResult<&[$name<T>]> {
+            validate(v, $is_valid)?;
+            // SAFETY: `$name<T>` is `repr(transparent)` over `T`, and all values are checked.
+            Ok(unsafe { core::slice::from_raw_parts(v.as_ptr() as *const $name<T>, v.len()) })
+        }
+
+        #[doc = concat!("Mutable version of [`", stringify!($as), "`].")]
+        #[inline]
+        pub fn $as_mut<T: SortableFloat>(v: &mut [T]) -> OrderResult<&mut [$name<T>]> {
+            validate(v, $is_valid)?;
+            // SAFETY: same as above. The wrapped values cannot be replaced by invalid values through the wrapper.
+            Ok(unsafe { core::slice::from_raw_parts_mut(v.as_mut_ptr() as *mut $name<T>, v.len()) })
+        }
+    };
+}
+
+float_wrapper!(
+    /// Float which is not NaN, so it implements [`Ord`].
+    ///
+    /// `-0.0` and `0.0` are equal, the same as [`PartialOrd`] for floats.
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use try_partialord::*;
+    ///
+    /// let mut v = vec![2.0, 1.0, 2.0, f64::INFINITY];
+    /// let values = try_as_not_nan_mut(&mut v).unwrap();
+    /// values.sort();
+    /// let counts = values.iter().fold(BTreeMap::new(), |mut m, x| {
+    ///     *m.entry(*x).or_insert(0) += 1;
+    ///     m
+    /// });
+    /// assert_eq!(counts[&NotNan::new(2.0).unwrap()], 2);
+    /// assert_eq!(v, [1.0, 2.0, 2.0, f64::INFINITY]);
+    ///
+    /// let err = try_as_not_nan(&[1.0, f64::NAN]).unwrap_err();
+    /// assert_eq!(err.operation(), OrderOperation::Validate);
+    /// assert_eq!(err.index(), Some(1));
+    /// ```
+    NotNan,
+    is_not_nan,
+    "NaN",
+    try_as_not_nan,
+    try_as_not_nan_mut
+);
+
+float_wrapper!(
+    /// Float which is neither infinite nor NaN, so it implements [`Ord`].
+    ///
+    /// `-0.0` and `0.0` are equal, the same as [`PartialOrd`] for floats.
+    /// ```
+    /// use try_partialord::*;
+    ///
+    /// let mut v = vec![1.0, 3.0, 3.0, 2.0];
+    /// let values = try_as_finite_mut(&mut v).unwrap();
+    /// values.sort_unstable();
+    /// assert_eq!(Finite::as_inner_slice(values), [1.0, 2.0, 3.0, 3.0]);
+    ///
+    /// assert!(try_as_finite(&[1.0, f64::INFINITY]).is_err());
+    /// ```
+    Finite,
+    is_finite,
+    "infinite or NaN",
+    try_as_finite,
+    try_as_finite_mut
+);
+
+#[inline]
+fn is_not_nan<T: SortableFloat>(x: T) -> bool {
+    !x.is_nan()
+}
+
+#[inline]
+fn is_finite<T: SortableFloat>(x: T) -> bool {
+    x.is_finite()
+}
+
+fn validate<T: SortableFloat>(v: &[T], is_valid: fn(T) -> bool) -> OrderResult<()> {
+    match v.iter().position(|&x| !is_valid(x)) {
+        Some(i) => Err(InvalidOrderError::with_index(OrderOperation::Validate, i)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use crate::*;
+    use rand::distributions::Standard;
+    use rand::prelude::*;
+    use std::collections::BinaryHeap;
+    use std::vec::Vec;
+
+    #[test]
+    fn try_as_not_nan_ok() {
+        let rng = thread_rng();
+        let mut v: Vec<f32> = Standard.sample_iter(rng).take(100).collect();
+        let mut sorted = v.clone();
+        sorted.try_sort().unwrap();
+        let heap: BinaryHeap<_> = try_as_not_nan(&v).unwrap().iter().copied().collect();
+        assert_eq!(heap.peek().map(|x| x.get()), sorted.last().copied());
+        try_as_finite_mut(&mut v).unwrap().sort();
+        assert_eq!(v, sorted);
+    }
+
+    #[test]
+    fn try_as_finite_error() {
+        let mut v = [0.0, -0.0, f64::NEG_INFINITY, f64::NAN];
+        assert_eq!(try_as_not_nan(&v).unwrap_err().index(), Some(3));
+        assert_eq!(try_as_finite_mut(&mut v).unwrap_err().index(), Some(2));
+        let values = try_as_not_nan(&v[..3]).unwrap();
+        assert_eq!(values[0], values[1]);
+        assert_eq!(
+            values.iter().min().map(|x| x.get()),
+            Some(f64::NEG_INFINITY)
+        );
+        assert_eq!(NotNan::new(f64::NAN), None);
+        assert_eq!(Finite::new(f64::INFINITY), None);
+    }
+}
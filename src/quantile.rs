@@ -0,0 +1,411 @@
+use crate::sort::{std_quicksort, FailureTracker};
+use crate::sorted::check_sorted_comparable;
+use crate::{ord_as_cmp, InvalidOrderError, OrderOperation, OrderResult};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// How to get the quantile when it lies between two elements `a <= b`, the same as `numpy.quantile`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub enum QuantileMethod {
+    /// `a`
+    Lower,
+    /// `b`
+    Higher,
+    /// `a` or `b`, whichever is nearest. Ties go to the even index.
+    Nearest,
+    /// Linear interpolation between `a` and `b`.
+    #[default]
+    Linear,
+    /// `(a + b) / 2`
+    Midpoint,
+}
+
+impl QuantileMethod {
+    fn discrete(self) -> Option<DiscreteQuantile> {
+        match self {
+            QuantileMethod::Lower => Some(DiscreteQuantile::Lower),
+            QuantileMethod::Higher => Some(DiscreteQuantile::Higher),
+            QuantileMethod::Nearest => Some(DiscreteQuantile::Nearest),
+            QuantileMethod::Linear | QuantileMethod::Midpoint => None,
+        }
+    }
+}
+
+/// [`QuantileMethod`] which returns one of the elements, so it does not need [`Interpolate`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub enum DiscreteQuantile {
+    /// `a`
+    Lower,
+    /// `b`
+    Higher,
+    /// `a` or `b`, whichever is nearest. Ties go to the even index.
+    #[default]
+    Nearest,
+}
+
+impl From<DiscreteQuantile> for QuantileMethod {
+    #[inline]
+    fn from(method: DiscreteQuantile) -> Self {
+        match method {
+            DiscreteQuantile::Lower => QuantileMethod::Lower,
+            DiscreteQuantile::Higher => QuantileMethod::Higher,
+            DiscreteQuantile::Nearest => QuantileMethod::Nearest,
+        }
+    }
+}
+
+/// Values which can be interpolated for [`QuantileMethod::Linear`] and [`QuantileMethod::Midpoint`].
+pub trait Interpolate: Copy {
+    /// Returns the value at `t` between `a` (`t = 0.0`) and `b` (`t = 1.0`).
+    fn interpolate(a: Self, b: Self, t: f64) -> Self;
+}
+
+impl Interpolate for f32 {
+    #[inline]
+    fn interpolate(a: Self, b: Self, t: f64) -> Self {
+        a + (b - a) * t as f32
+    }
+}
+
+impl Interpolate for f64 {
+    #[inline]
+    fn interpolate(a: Self, b: Self, t: f64) -> Self {
+        a + (b - a) * t
+    }
+}
+
+/// Median and quantile methods for [`PartialOrd`], using selection instead of sorting.
+///
+/// These reorder the slice in the same way as [`try_select_nth_unstable`](`crate::TrySort::try_select_nth_unstable`).
+/// If `q` is not in `0.0..=1.0`, including NaN, they return error with [`is_out_of_range`](`InvalidOrderError::is_out_of_range`)
+/// and the position of `q` in the given quantiles.
+/// ```
+/// use try_partialord::*;
+///
+/// let mut v = [4.0, 1.0, 3.0, 2.0];
+/// assert_eq!(v.try_median(), Ok(Some(2.5)));
+/// assert_eq!(v.try_quantile(0.25, QuantileMethod::Lower), Ok(Some(1.0)));
+/// assert_eq!(v.try_quantile(0.25, QuantileMethod::Linear), Ok(Some(1.75)));
+/// assert_eq!(
+///     v.try_quantiles(&[0.0, 0.5, 1.0], QuantileMethod::Higher),
+///     Ok(vec![1.0, 3.0, 4.0])
+/// );
+///
+/// let mut v = [4.0, f64::NAN, 3.0, 2.0];
+/// assert!(v.try_median().is_err());
+///
+/// let mut v = [3u32, 1, 2, 4];
+/// assert_eq!(v.try_quantile_discrete(0.5, DiscreteQuantile::Lower), Ok(Some(2)));
+/// assert!(v.try_quantile_discrete(1.5, DiscreteQuantile::Lower).unwrap_err().is_out_of_range());
+/// ```
+pub trait TryQuantile<T> {
+    /// Returns the median, or [`None`] if empty. For even length, this is the mean of the middle two elements.
+    #[inline]
+    fn try_median(&mut self) -> OrderResult<Option<T>>
+    where
+        T: PartialOrd<T> + Interpolate,
+    {
+        self.try_quantile(0.5, QuantileMethod::Linear)
+    }
+    /// Returns the `q`-th quantile, or [`None`] if empty.
+    fn try_quantile(&mut self, q: f64, method: QuantileMethod) -> OrderResult<Option<T>>
+    where
+        T: PartialOrd<T> + Interpolate;
+    /// Returns the quantiles for each of `qs`, or an empty [`Vec`] if empty.
+    ///
+    /// This partitions the slice once for all `qs`, instead of selecting each of them from scratch.
+    #[cfg(feature = "std")]
+    fn try_quantiles(&mut self, qs: &[f64], method: QuantileMethod) -> OrderResult<Vec<T>>
+    where
+        T: PartialOrd<T> + Interpolate;
+    /// Returns the `q`-th quantile, or [`None`] if empty, choosing one of the elements without interpolation.
+    fn try_quantile_discrete(&mut self, q: f64, method: DiscreteQuantile) -> OrderResult<Option<T>>
+    where
+        T: PartialOrd<T> + Clone;
+    /// Returns the quantiles for each of `qs` without interpolation, or an empty [`Vec`] if empty.
+    #[cfg(feature = "std")]
+    fn try_quantiles_discrete(
+        &mut self,
+        qs: &[f64],
+        method: DiscreteQuantile,
+    ) -> OrderResult<Vec<T>>
+    where
+        T: PartialOrd<T> + Clone;
+}
+
+impl<T> TryQuantile<T> for [T] {
+    fn try_quantile(&mut self, q: f64, method: QuantileMethod) -> OrderResult<Option<T>>
+    where
+        T: PartialOrd<T> + Interpolate,
+    {
+        check_quantile(q, 0)?;
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let pos = Position::new(self.len(), q);
+        let indices = [pos.lower, pos.higher];
+        let indices = if pos.lower == pos.higher {
+            &indices[..1]
+        } else {
+            &indices[..]
+        };
+        try_select_many(self, indices)?;
+        Ok(Some(pos.value(self, method)))
+    }
+
+    #[cfg(feature = "std")]
+    fn try_quantiles(&mut self, qs: &[f64], method: QuantileMethod) -> OrderResult<Vec<T>>
+    where
+        T: PartialOrd<T> + Interpolate,
+    {
+        for (i, &q) in qs.iter().enumerate() {
+            check_quantile(q, i)?;
+        }
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+        let positions: Vec<_> = qs.iter().map(|&q| Position::new(self.len(), q)).collect();
+        let mut indices: Vec<_> = positions
+            .iter()
+            .flat_map(|p| core::iter::once(p.lower).chain(core::iter::once(p.higher)))
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        try_select_many(self, &indices)?;
+        Ok(positions.iter().map(|p| p.value(self, method)).collect())
+    }
+
+    fn try_quantile_discrete(&mut self, q: f64, method: DiscreteQuantile) -> OrderResult<Option<T>>
+    where
+        T: PartialOrd<T> + Clone,
+    {
+        check_quantile(q, 0)?;
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let index = Position::new(self.len(), q).index(method);
+        try_select_many(self, &[index])?;
+        Ok(Some(self[index].clone()))
+    }
+
+    #[cfg(feature = "std")]
+    fn try_quantiles_discrete(
+        &mut self,
+        qs: &[f64],
+        method: DiscreteQuantile,
+    ) -> OrderResult<Vec<T>>
+    where
+        T: PartialOrd<T> + Clone,
+    {
+        for (i, &q) in qs.iter().enumerate() {
+            check_quantile(q, i)?;
+        }
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+        let positions: Vec<_> = qs
+            .iter()
+            .map(|&q| Position::new(self.len(), q).index(method))
+            .collect();
+        let mut indices = positions.clone();
+        indices.sort_unstable();
+        indices.dedup();
+        try_select_many(self, &indices)?;
+        Ok(positions.iter().map(|&i| self[i].clone()).collect())
+    }
+}
+
+/// Checks that `q`, which is the `index`-th quantile, is in `0.0..=1.0`.
+fn check_quantile(q: f64, index: usize) -> OrderResult<()> {
+    if (0.0..=1.0).contains(&q) {
+        Ok(())
+    } else {
+        Err(InvalidOrderError::out_of_range(
+            OrderOperation::Select,
+            index,
+        ))
+    }
+}
+
+/// Position of the quantile between the elements at `lower` and `higher` in the sorted slice.
+struct Position {
+    lower: usize,
+    higher: usize,
+    fraction: f64,
+}
+
+impl Position {
+    /// `len` must not be zero and `q` must be in `0.0..=1.0`.
+    fn new(len: usize, q: f64) -> Self {
+        let h = (len - 1) as f64 * q;
+        // `h` is not negative, so this is the same as `floor`.
+        let lower = (h as usize).min(len - 1);
+        let fraction = h - lower as f64;
+        let higher = if fraction > 0.0 { lower + 1 } else { lower };
+        Position {
+            lower,
+            higher,
+            fraction,
+        }
+    }
+
+    /// Returns the index of the element chosen by `method`.
+    fn index(&self, method: DiscreteQuantile) -> usize {
+        match method {
+            DiscreteQuantile::Lower => self.lower,
+            DiscreteQuantile::Higher => self.higher,
+            DiscreteQuantile::Nearest => {
+                if self.fraction < 0.5 || (self.fraction == 0.5 && self.lower & 1 == 0) {
+                    self.lower
+                } else {
+                    self.higher
+                }
+            }
+        }
+    }
+
+    /// Gets the quantile from `v`, where the elements at `lower` and `higher` are selected.
+    fn value<T: Interpolate>(&self, v: &[T], method: QuantileMethod) -> T {
+        let (a, b) = (v[self.lower], v[self.higher]);
+        if self.lower == self.higher {
+            return a;
+        }
+        match method.discrete() {
+            Some(method) => v[self.index(method)],
+            None if method == QuantileMethod::Midpoint => T::interpolate(a, b, 0.5),
+            None => T::interpolate(a, b, self.fraction),
+        }
+    }
+}
+
+/// Reorders `v` so that each of `indices` has the element at its sorted position.
+///
+/// `indices` must be sorted, without duplicates and less than `v.len()`.
+fn try_select_many<T: PartialOrd<T>>(v: &mut [T], indices: &[usize]) -> OrderResult<()> {
+    check_sorted_comparable(v, OrderOperation::Select)?;
+    let mut compare = ord_as_cmp;
+    let mut tracker = FailureTracker::new(v);
+    let selected = {
+        let mut is_less = tracker.wrap(&mut compare);
+        select_many(v, 0, indices, &mut is_less)
+    };
+    selected.map_err(|()| tracker.error(v, &mut compare, OrderOperation::Select))
+}
+
+/// `v` is the part of the slice starting at `offset`, and `indices` are the positions in the whole slice.
+fn select_many<T, E, F>(
+    v: &mut [T],
+    offset: usize,
+    indices: &[usize],
+    is_less: &mut F,
+) -> Result<(), E>
+where
+    F: FnMut(&T, &T) -> Result<bool, E>,
+{
+    if indices.is_empty() {
+        return Ok(());
+    }
+    // Select the middle one first, so that both sides are selected in the smaller parts.
+    let mid = indices.len() / 2;
+    let nth = indices[mid] - offset;
+    std_quicksort::partition_at_index(v, nth, &mut *is_less)?;
+    let (left, right) = v.split_at_mut(nth);
+    select_many(left, offset, &indices[..mid], is_less)?;
+    select_many(
+        &mut right[1..],
+        offset + nth + 1,
+        &indices[mid + 1..],
+        is_less,
+    )
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use crate::*;
+    use rand::distributions::Standard;
+    use rand::prelude::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn try_quantiles_ok() {
+        let rng = thread_rng();
+        let mut v: Vec<f64> = Standard.sample_iter(rng).take(101).collect();
+        let mut sorted = v.clone();
+        sorted.try_sort().unwrap();
+        let qs = [0.0, 0.1, 0.25, 0.333, 0.5, 0.9, 1.0];
+        let expected: Vec<_> = qs
+            .iter()
+            .map(|q| {
+                let h = 100.0 * q;
+                let lo = h as usize;
+                let hi = (lo + 1).min(100);
+                sorted[lo] + (sorted[hi] - sorted[lo]) * (h - lo as f64)
+            })
+            .collect();
+        let res = v
+            .clone()
+            .try_quantiles(&qs, QuantileMethod::Linear)
+            .unwrap();
+        for (a, b) in res.iter().zip(&expected) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        for (q, b) in qs.iter().zip(&expected) {
+            let a = v.try_quantile(*q, QuantileMethod::Linear).unwrap().unwrap();
+            assert!((a - b).abs() < 1e-12);
+        }
+        assert_eq!(v.try_median(), Ok(Some(sorted[50])));
+    }
+
+    #[test]
+    fn try_quantile_methods() {
+        let mut v = [5.0, 1.0, 4.0, 2.0, 3.0];
+        let q = |v: &mut [f32], q, m| v.try_quantile(q, m).unwrap().unwrap();
+        assert_eq!(q(&mut v, 0.3, QuantileMethod::Lower), 2.0);
+        assert_eq!(q(&mut v, 0.3, QuantileMethod::Higher), 3.0);
+        assert_eq!(q(&mut v, 0.3, QuantileMethod::Nearest), 2.0);
+        assert_eq!(q(&mut v, 0.375, QuantileMethod::Nearest), 3.0);
+        assert_eq!(q(&mut v, 0.625, QuantileMethod::Nearest), 3.0);
+        assert_eq!(q(&mut v, 0.3, QuantileMethod::Midpoint), 2.5);
+        assert!((q(&mut v, 0.3, QuantileMethod::Linear) - 2.2).abs() < 1e-6);
+        assert_eq!(v.try_median(), Ok(Some(3.0)));
+        assert_eq!([0.0f32; 0].try_median(), Ok(None));
+        let err = [f64::NAN].try_median().unwrap_err();
+        assert_eq!(
+            (err.operation(), err.index()),
+            (OrderOperation::Select, Some(0))
+        );
+        assert!([f64::NAN]
+            .try_quantile_discrete(0.5, DiscreteQuantile::Lower)
+            .is_err());
+
+        let mut v = [5.0, 1.0, f32::NAN, 2.0, 3.0];
+        let err = v
+            .try_quantiles(&[0.1, 0.9], QuantileMethod::Linear)
+            .unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::Select);
+
+        let err = v
+            .try_quantiles(&[0.5, f64::NAN], QuantileMethod::Lower)
+            .unwrap_err();
+        assert!(err.is_out_of_range());
+        assert_eq!(err.index(), Some(1));
+        assert!([0.0f32; 0]
+            .try_quantile(-0.5, QuantileMethod::Linear)
+            .is_err());
+
+        let mut v: Vec<u32> = (0..10).rev().collect();
+        let qs = [0.0, 0.25, 0.5, 0.75, 1.0];
+        let expected = [0, 2, 4, 7, 9];
+        assert_eq!(
+            v.try_quantiles_discrete(&qs, DiscreteQuantile::Nearest),
+            Ok(expected.to_vec())
+        );
+        for (q, e) in qs.iter().zip(&expected) {
+            assert_eq!(
+                v.try_quantile_discrete(*q, DiscreteQuantile::Nearest),
+                Ok(Some(*e))
+            );
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{ord_as_cmp, FloatOrder, InvalidOrderError, OrderOperation, OrderResult};
+use crate::{SortableFloat, TrySort};
+use std::vec::Vec;
+
+/// How to rank equal elements, the same as `scipy.stats.rankdata`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub enum RankMethod {
+    /// The average of the ranks which equal elements would have.
+    #[default]
+    Average,
+    /// The smallest rank which equal elements would have.
+    Min,
+    /// The largest rank which equal elements would have.
+    Max,
+    /// Like `Min`, but the next rank after equal elements is the next number instead of skipping.
+    Dense,
+    /// Distinct ranks in the order the elements appear in the slice.
+    Ordinal,
+}
+
+/// Ranking methods for [`PartialOrd`].
+///
+/// Ranks start from `1.0`, and are computed with the stable sort so [`RankMethod::Ordinal`] is deterministic.
+/// ```
+/// use try_partialord::*;
+///
+/// let v = [2.0, 1.0, 2.0, 4.0];
+/// assert_eq!(v.try_rank(RankMethod::Average), Ok(vec![2.5, 1.0, 2.5, 4.0]));
+/// assert_eq!(v.try_rank(RankMethod::Min), Ok(vec![2.0, 1.0, 2.0, 4.0]));
+/// assert_eq!(v.try_rank(RankMethod::Max), Ok(vec![3.0, 1.0, 3.0, 4.0]));
+/// assert_eq!(v.try_rank(RankMethod::Dense), Ok(vec![2.0, 1.0, 2.0, 3.0]));
+/// assert_eq!(v.try_rank(RankMethod::Ordinal), Ok(vec![2.0, 1.0, 3.0, 4.0]));
+///
+/// let v = [2.0, f64::NAN, 1.0];
+/// assert!(v.try_rank(RankMethod::Average).is_err());
+/// let order = FloatOrder::new(NanPolicy::Last);
+/// assert_eq!(v.try_rank_float(RankMethod::Average, order), Ok(vec![2.0, 3.0, 1.0]));
+/// ```
+pub trait TryRank<T> {
+    /// Returns the rank of each element.
+    #[inline]
+    fn try_rank(&self, method: RankMethod) -> OrderResult<Vec<f64>>
+    where
+        T: PartialOrd<T>,
+    {
+        self.try_rank_by(ord_as_cmp, method)
+    }
+    /// Returns the rank of each element with the comparator.
+    fn try_rank_by<F>(&self, compare: F, method: RankMethod) -> OrderResult<Vec<f64>>
+    where
+        F: FnMut(&T, &T) -> Option<bool>;
+    /// Returns the rank of each float, using `order` for NaN.
+    ///
+    /// With [`NanPolicy::First`](`crate::NanPolicy::First`) or [`NanPolicy::Last`](`crate::NanPolicy::Last`), all NaN are ranked as equal.
+    #[inline]
+    fn try_rank_float(&self, method: RankMethod, order: FloatOrder) -> OrderResult<Vec<f64>>
+    where
+        T: SortableFloat,
+    {
+        self.try_rank_by(|a, b| order.is_less(*a, *b), method)
+    }
+}
+
+impl<T> TryRank<T> for [T] {
+    fn try_rank_by<F>(&self, compare: F, method: RankMethod) -> OrderResult<Vec<f64>>
+    where
+        F: FnMut(&T, &T) -> Option<bool>,
+    {
+        let mut compare = compare;
+        let indices = self.try_argsort_by(&mut compare)?;
+        let mut ranks = vec![0.0; self.len()];
+        let mut start = 0;
+        let mut dense = 0;
+        while start < indices.len() {
+            // The slice is sorted by `indices`, so the elements in a group are equal when the next one is not greater.
+            let mut end = start + 1;
+            while end < indices.len() {
+                let (a, b) = (indices[end - 1], indices[end]);
+                match compare(&self[a], &self[b]) {
+                    Some(false) => end += 1,
+                    Some(true) => break,
+                    None => {
+                        return Err(InvalidOrderError::with_indices(OrderOperation::Sort, a, b))
+                    }
+                }
+            }
+            dense += 1;
+            for (i, &index) in indices[start..end].iter().enumerate() {
+                ranks[index] = match method {
+                    RankMethod::Average => (start + 1 + end) as f64 / 2.0,
+                    RankMethod::Min => (start + 1) as f64,
+                    RankMethod::Max => end as f64,
+                    RankMethod::Dense => dense as f64,
+                    RankMethod::Ordinal => (start + i + 1) as f64,
+                };
+            }
+            start = end;
+        }
+        Ok(ranks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::distributions::Standard;
+    use rand::prelude::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn try_rank_ok() {
+        let rng = thread_rng();
+        let v: Vec<f64> = Standard.sample_iter(rng).take(100).collect();
+        let indices = v.try_argsort().unwrap();
+        for method in [RankMethod::Average, RankMethod::Min, RankMethod::Dense] {
+            let ranks = v.try_rank(method).unwrap();
+            for (i, &index) in indices.iter().enumerate() {
+                assert_eq!(ranks[index], (i + 1) as f64);
+            }
+        }
+    }
+
+    #[test]
+    fn try_rank_ties() {
+        let v = [(3, 1.0), (1, 0.5), (2, 1.0), (4, 1.0)];
+        let by_second = |a: &(i32, f64), b: &(i32, f64)| a.1.partial_cmp(&b.1).map(|o| o.is_lt());
+        assert_eq!(
+            v.try_rank_by(by_second, RankMethod::Ordinal),
+            Ok(vec![2.0, 1.0, 3.0, 4.0])
+        );
+        assert_eq!(
+            v.try_rank_by(by_second, RankMethod::Average),
+            Ok(vec![3.0, 1.0, 3.0, 3.0])
+        );
+
+        let v = [1.0, f32::NAN, 0.0, f32::NAN];
+        let err = v.try_rank(RankMethod::Min).unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::Sort);
+        let ranks = v.try_rank_float(RankMethod::Min, FloatOrder::new(NanPolicy::First));
+        assert_eq!(ranks, Ok(vec![4.0, 1.0, 3.0, 1.0]));
+    }
+}
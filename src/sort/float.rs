@@ -0,0 +1,231 @@
+use super::TrySort;
+use crate::OrderResult;
+
+/// Where to put NaN when sorting floats.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub enum NanPolicy {
+    /// Returns [`InvalidOrderError`](`crate::InvalidOrderError`) if there is NaN, same as [`try_sort`](`TrySort::try_sort`).
+    #[default]
+    Error,
+    /// Puts NaN before all other values.
+    First,
+    /// Puts NaN after all other values.
+    Last,
+}
+
+/// How to order floats in [`TrySortFloat`].
+///
+/// The default is the same order as [`PartialOrd`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct FloatOrder {
+    /// Where to put NaN.
+    pub nan: NanPolicy,
+    /// If `true`, `-0.0` is ordered before `0.0`. Otherwise they are equal.
+    pub signed_zero: bool,
+}
+
+impl FloatOrder {
+    /// Creates the order with `nan` policy, treating `-0.0` and `0.0` as equal.
+    pub const fn new(nan: NanPolicy) -> Self {
+        FloatOrder {
+            nan,
+            signed_zero: false,
+        }
+    }
+
+    /// Returns `true` if `a` should be before `b`, or [`None`] if they cannot be compared.
+    pub(crate) fn is_less<K: SortableFloat>(self, a: K, b: K) -> Option<bool> {
+        match (a.is_nan(), b.is_nan()) {
+            (false, false) => match a.partial_cmp(&b)? {
+                core::cmp::Ordering::Equal => {
+                    Some(self.signed_zero && a.is_sign_negative() && !b.is_sign_negative())
+                }
+                ord => Some(ord == core::cmp::Ordering::Less),
+            },
+            (true, true) if self.nan != NanPolicy::Error => Some(false),
+            (a_nan, _) => match self.nan {
+                NanPolicy::Error => None,
+                NanPolicy::First => Some(a_nan),
+                NanPolicy::Last => Some(!a_nan),
+            },
+        }
+    }
+}
+
+/// Float types which can be sorted with [`TrySortFloat`].
+pub trait SortableFloat: PartialOrd + Copy {
+    /// Returns `true` if this value is NaN.
+    fn is_nan(self) -> bool;
+    /// Returns `true` if this value has negative sign, including `-0.0`.
+    fn is_sign_negative(self) -> bool;
+    /// Returns `true` if this value is neither infinite nor NaN.
+    fn is_finite(self) -> bool;
+}
+
+macro_rules! impl_sortable_float {
+    ($($t:ty),*) => {$(
+        impl SortableFloat for $t {
+            #[inline]
+            fn is_nan(self) -> bool {
+                <$t>::is_nan(self)
+            }
+            #[inline]
+            fn is_sign_negative(self) -> bool {
+                <$t>::is_sign_negative(self)
+            }
+            #[inline]
+            fn is_finite(self) -> bool {
+                <$t>::is_finite(self)
+            }
+        }
+    )*};
+}
+
+impl_sortable_float!(f32, f64);
+
+/// Sort methods for floats with the placement of NaN.
+///
+/// These return the number of NaN which were moved to the front or back.
+/// Without NaN, the result is the same as [`TrySort`].
+/// ```
+/// use try_partialord::*;
+///
+/// let mut v = vec![2.0, f64::NAN, -0.0, 1.0, 0.0];
+/// assert!(v.try_sort_unstable_float(FloatOrder::default()).is_err());
+///
+/// let order = FloatOrder { nan: NanPolicy::Last, signed_zero: true };
+/// assert_eq!(v.try_sort_unstable_float(order), Ok(1));
+/// assert_eq!(&v[..4], &[-0.0, 0.0, 1.0, 2.0]);
+/// assert!(v[0].is_sign_negative());
+/// assert!(v[4].is_nan());
+/// ```
+pub trait TrySortFloat<T> {
+    #[cfg(feature = "std")]
+    #[inline]
+    /// Stable sort for floats, using `order`.
+    fn try_sort_float(&mut self, order: FloatOrder) -> OrderResult<usize>
+    where
+        T: SortableFloat,
+    {
+        self.try_sort_by_float_key(|x| *x, order)
+    }
+    #[cfg(feature = "std")]
+    /// Stable sort by float key, using `order`.
+    fn try_sort_by_float_key<K, F>(&mut self, f: F, order: FloatOrder) -> OrderResult<usize>
+    where
+        F: FnMut(&T) -> K,
+        K: SortableFloat;
+
+    #[inline]
+    /// Unstable sort for floats, using `order`.
+    fn try_sort_unstable_float(&mut self, order: FloatOrder) -> OrderResult<usize>
+    where
+        T: SortableFloat,
+    {
+        self.try_sort_unstable_by_float_key(|x| *x, order)
+    }
+    /// Unstable sort by float key, using `order`.
+    fn try_sort_unstable_by_float_key<K, F>(
+        &mut self,
+        f: F,
+        order: FloatOrder,
+    ) -> OrderResult<usize>
+    where
+        F: FnMut(&T) -> K,
+        K: SortableFloat;
+}
+
+impl<T> TrySortFloat<T> for [T] {
+    #[cfg(feature = "std")]
+    fn try_sort_by_float_key<K, F>(&mut self, f: F, order: FloatOrder) -> OrderResult<usize>
+    where
+        F: FnMut(&T) -> K,
+        K: SortableFloat,
+    {
+        let mut f = f;
+        let nans = count_nan(self, &mut f, order);
+        self.try_sort_by(|a, b| order.is_less(f(a), f(b)))?;
+        Ok(nans)
+    }
+
+    fn try_sort_unstable_by_float_key<K, F>(
+        &mut self,
+        f: F,
+        order: FloatOrder,
+    ) -> OrderResult<usize>
+    where
+        F: FnMut(&T) -> K,
+        K: SortableFloat,
+    {
+        let mut f = f;
+        let nans = count_nan(self, &mut f, order);
+        self.try_sort_unstable_by(|a, b| order.is_less(f(a), f(b)))?;
+        Ok(nans)
+    }
+}
+
+fn count_nan<T, K, F>(v: &[T], f: &mut F, order: FloatOrder) -> usize
+where
+    F: FnMut(&T) -> K,
+    K: SortableFloat,
+{
+    if order.nan == NanPolicy::Error {
+        0
+    } else {
+        v.iter().filter(|x| f(x).is_nan()).count()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use crate::*;
+    use rand::distributions::Standard;
+    use rand::prelude::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn try_sort_float_nan() {
+        let rng = thread_rng();
+        let mut v: Vec<f64> = Standard.sample_iter(rng).take(100).collect();
+        let mut sorted = v.clone();
+        sorted.try_sort().unwrap();
+        v.insert(10, f64::NAN);
+        v.insert(50, f64::NAN);
+
+        let mut first = v.clone();
+        assert_eq!(
+            first.try_sort_float(FloatOrder::new(NanPolicy::First)),
+            Ok(2)
+        );
+        assert!(first[..2].iter().all(|x| x.is_nan()));
+        assert_eq!(&first[2..], &sorted[..]);
+
+        let mut last = v.clone();
+        let order = FloatOrder::new(NanPolicy::Last);
+        assert_eq!(last.try_sort_unstable_float(order), Ok(2));
+        assert!(last[100..].iter().all(|x| x.is_nan()));
+        assert_eq!(&last[..100], &sorted[..]);
+
+        assert!(v.try_sort_float(FloatOrder::default()).is_err());
+    }
+
+    #[test]
+    fn try_sort_by_float_key_signed_zero() {
+        let mut v = [(0.0, 0), (-0.0, 1), (1.0, 2), (-0.0, 3), (f32::NAN, 4)];
+        let order = FloatOrder {
+            nan: NanPolicy::First,
+            signed_zero: true,
+        };
+        assert_eq!(v.try_sort_by_float_key(|x| x.0, order), Ok(1));
+        let ids: Vec<_> = v.iter().map(|x| x.1).collect();
+        assert_eq!(ids, [4, 1, 3, 0, 2]);
+
+        assert_eq!(
+            v.try_sort_by_float_key(|x| x.0, FloatOrder::new(NanPolicy::Last)),
+            Ok(1)
+        );
+        let ids: Vec<_> = v.iter().map(|x| x.1).collect();
+        assert_eq!(ids, [1, 3, 0, 2, 4]);
+    }
+}
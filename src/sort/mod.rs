@@ -1,8 +1,36 @@
-use crate::{ord_as_cmp, InvalidOrderError, OrderResult};
+use crate::{ord_as_cmp, InvalidOrderError, OrderOperation, OrderResult};
 use core::cmp::Ordering;
+mod float;
 #[cfg(feature = "std")]
 mod std_mergesort;
-mod std_quicksort;
+pub(crate) mod std_quicksort;
+pub use float::*;
+
+/// Runs `$body` with the type `$i` set to the smallest index type which can index `$len` elements,
+/// if it makes `($key, $i)` smaller.
+#[cfg(feature = "std")]
+macro_rules! with_compact_index {
+    ($key:ty, $len:expr, $i:ident => $body:expr) => {{
+        use core::mem::size_of;
+        let len = $len;
+        if size_of::<($key, u8)>() < size_of::<($key, u16)>() && len <= (u8::MAX as usize) {
+            type $i = u8;
+            $body
+        } else if size_of::<($key, u16)>() < size_of::<($key, u32)>() && len <= (u16::MAX as usize)
+        {
+            type $i = u16;
+            $body
+        } else if size_of::<($key, u32)>() < size_of::<($key, usize)>()
+            && len <= (u32::MAX as usize)
+        {
+            type $i = u32;
+            $body
+        } else {
+            type $i = usize;
+            $body
+        }
+    }};
+}
 
 /// Sort methods for [`PartialOrd`].
 pub trait TrySort<T> {
@@ -22,6 +50,50 @@ pub trait TrySort<T> {
         F: FnMut(&T, &T) -> Option<bool>;
     #[cfg(feature = "std")]
     #[inline]
+    /// [`PartialOrd`] version for [`slice::sort_by`], taking the same comparator as [`try_min_by`](`crate::TryMinMax::try_min_by`).
+    /// ```
+    /// use try_partialord::*;
+    ///
+    /// let mut v = vec![(1, 2.0), (2, 1.0), (3, 2.0)];
+    /// let by_second = |a: &(i32, f64), b: &(i32, f64)| a.1.partial_cmp(&b.1);
+    /// assert!(v.try_sort_by_partial_cmp(by_second).is_ok());
+    /// assert_eq!(v, [(2, 1.0), (1, 2.0), (3, 2.0)]);
+    /// assert_eq!(v.try_is_sorted_by_partial_cmp(by_second), Ok(true));
+    /// assert_eq!(v.iter().try_min_by(|a, b| by_second(a, b)), Ok(Some(&(2, 1.0))));
+    /// ```
+    fn try_sort_by_partial_cmp<F>(&mut self, compare: F) -> OrderResult<()>
+    where
+        F: FnMut(&T, &T) -> Option<Ordering>,
+    {
+        let mut compare = compare;
+        self.try_sort_by(|a, b| compare(a, b).map(|o| o == Ordering::Less))
+    }
+    #[cfg(feature = "std")]
+    /// Version of [`slice::sort_by`] with the comparator which can fail with your own error.
+    ///
+    /// The error returned by `compare` is returned as is.
+    /// ```
+    /// use try_partialord::*;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct UnknownId(usize);
+    ///
+    /// let table = [3, 1, 2];
+    /// let rank = |i: &usize| table.get(*i).ok_or(UnknownId(*i));
+    /// let by_rank = |a: &usize, b: &usize| -> Result<_, UnknownId> { Ok(rank(a)?.cmp(rank(b)?)) };
+    ///
+    /// let mut v = vec![0, 1, 2];
+    /// assert_eq!(v.try_sort_by_result(by_rank), Ok(()));
+    /// assert_eq!(v, [1, 2, 0]);
+    ///
+    /// v.push(5);
+    /// assert_eq!(v.try_sort_by_result(by_rank), Err(UnknownId(5)));
+    /// ```
+    fn try_sort_by_result<E, F>(&mut self, compare: F) -> Result<(), E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>;
+    #[cfg(feature = "std")]
+    #[inline]
     /// [`PartialOrd`] version for [`slice::sort_by_key`]
     fn try_sort_by_key<K, F>(&mut self, f: F) -> OrderResult<()>
     where
@@ -32,6 +104,22 @@ pub trait TrySort<T> {
         self.try_sort_by(|a, b| f2(a).partial_cmp(&f2(b)).map(|a| a == Ordering::Less))
     }
     #[cfg(feature = "std")]
+    #[inline]
+    /// Same as [`try_sort`](`TrySort::try_sort`), but the slice is left unchanged when it returns error.
+    fn try_sort_atomic(&mut self) -> OrderResult<()>
+    where
+        T: PartialOrd<T>,
+    {
+        self.try_sort_atomic_by(ord_as_cmp)
+    }
+    #[cfg(feature = "std")]
+    /// Same as [`try_sort_by`](`TrySort::try_sort_by`), but the slice is left unchanged when it returns error.
+    ///
+    /// This sorts the indices first and moves the elements only after the sort succeeded, so it needs `O(n)` extra memory.
+    fn try_sort_atomic_by<F>(&mut self, compare: F) -> OrderResult<()>
+    where
+        F: FnMut(&T, &T) -> Option<bool>;
+    #[cfg(feature = "std")]
     /// [`PartialOrd`] version for [`slice::sort_by_cached_key`]
     fn try_sort_by_cached_key<K, F>(&mut self, f: F) -> OrderResult<()>
     where
@@ -51,6 +139,21 @@ pub trait TrySort<T> {
     where
         F: FnMut(&T, &T) -> Option<bool>;
     #[inline]
+    /// [`PartialOrd`] version for [`slice::sort_unstable_by`], taking the same comparator as [`try_min_by`](`crate::TryMinMax::try_min_by`).
+    fn try_sort_unstable_by_partial_cmp<F>(&mut self, compare: F) -> OrderResult<()>
+    where
+        F: FnMut(&T, &T) -> Option<Ordering>,
+    {
+        let mut compare = compare;
+        self.try_sort_unstable_by(|a, b| compare(a, b).map(|o| o == Ordering::Less))
+    }
+    /// Version of [`slice::sort_unstable_by`] with the comparator which can fail with your own error.
+    ///
+    /// The error returned by `compare` is returned as is.
+    fn try_sort_unstable_by_result<E, F>(&mut self, compare: F) -> Result<(), E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>;
+    #[inline]
     /// [`PartialOrd`] version for [`slice::sort_unstable_by_key`]
     fn try_sort_unstable_by_key<K, F>(&mut self, f: F) -> OrderResult<()>
     where
@@ -61,8 +164,184 @@ pub trait TrySort<T> {
         self.try_sort_unstable_by(|a, b| f2(a).partial_cmp(&f2(b)).map(|a| a == Ordering::Less))
     }
 
+    #[inline]
+    /// Sorts only the smallest `k` elements into the front of the slice, like `std::partial_sort` in C++.
+    ///
+    /// The order of the other elements is unspecified. If `k` is larger than the length, the whole slice is sorted.
+    /// ```
+    /// use try_partialord::*;
+    ///
+    /// let mut v = [5.0, 1.0, 4.0, 2.0, 3.0];
+    /// assert!(v.try_partial_sort(2).is_ok());
+    /// assert_eq!(&v[..2], &[1.0, 2.0]);
+    /// ```
+    fn try_partial_sort(&mut self, k: usize) -> OrderResult<()>
+    where
+        T: PartialOrd<T>,
+    {
+        self.try_partial_sort_by(k, ord_as_cmp)
+    }
+    /// Sorts only the smallest `k` elements into the front of the slice with the comparator.
+    fn try_partial_sort_by<F>(&mut self, k: usize, compare: F) -> OrderResult<()>
+    where
+        F: FnMut(&T, &T) -> Option<bool>;
+    /// Same as [`try_partial_sort_by`](`TrySort::try_partial_sort_by`), but `compare` can fail with your own error.
+    ///
+    /// The error returned by `compare` is returned as is.
+    fn try_partial_sort_by_result<E, F>(&mut self, k: usize, compare: F) -> Result<(), E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>;
+    #[inline]
+    /// Sorts only the smallest `k` elements into the front of the slice with the key extraction function.
+    fn try_partial_sort_by_key<K, F>(&mut self, k: usize, f: F) -> OrderResult<()>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>,
+    {
+        let mut f2 = f;
+        self.try_partial_sort_by(k, |a, b| {
+            f2(a).partial_cmp(&f2(b)).map(|a| a == Ordering::Less)
+        })
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    /// Same as [`try_sort_unstable`](`TrySort::try_sort_unstable`), but the slice is left unchanged when it returns error.
+    fn try_sort_unstable_atomic(&mut self) -> OrderResult<()>
+    where
+        T: PartialOrd<T>,
+    {
+        self.try_sort_unstable_atomic_by(ord_as_cmp)
+    }
+    #[cfg(feature = "std")]
+    /// Same as [`try_sort_unstable_by`](`TrySort::try_sort_unstable_by`), but the slice is left unchanged when it returns error.
+    ///
+    /// This sorts the indices first and moves the elements only after the sort succeeded, so it needs `O(n)` extra memory.
+    fn try_sort_unstable_atomic_by<F>(&mut self, compare: F) -> OrderResult<()>
+    where
+        F: FnMut(&T, &T) -> Option<bool>;
+
+    #[cfg(feature = "std")]
+    #[inline]
+    /// Returns the permutation which would stably sort the slice, without moving the elements.
+    ///
+    /// `v[indices[0]]` is the smallest element, and so on, so the same permutation can reorder other slices.
+    /// ```
+    /// use try_partialord::*;
+    ///
+    /// let prices = [3.5, 1.25, 2.0];
+    /// let names = ["c", "a", "b"];
+    /// let indices = prices.try_argsort().unwrap();
+    /// assert_eq!(indices, [1, 2, 0]);
+    /// let names: Vec<_> = indices.iter().map(|&i| names[i]).collect();
+    /// assert_eq!(names, ["a", "b", "c"]);
+    /// ```
+    fn try_argsort(&self) -> OrderResult<Vec<usize>>
+    where
+        T: PartialOrd<T>,
+    {
+        self.try_argsort_by(ord_as_cmp)
+    }
+    #[cfg(feature = "std")]
+    /// Returns the permutation which would stably sort the slice by `compare`, without moving the elements.
+    fn try_argsort_by<F>(&self, compare: F) -> OrderResult<Vec<usize>>
+    where
+        F: FnMut(&T, &T) -> Option<bool>;
+    #[cfg(feature = "std")]
+    /// Returns the permutation which would stably sort the slice by key, without moving the elements.
+    ///
+    /// `f` is called once for each element, the same as [`try_sort_by_cached_key`](`TrySort::try_sort_by_cached_key`).
+    fn try_argsort_by_key<K, F>(&self, f: F) -> OrderResult<Vec<usize>>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>;
+    #[cfg(feature = "std")]
+    #[inline]
+    /// Returns the permutation which would sort the slice, not preserving the order of equal elements.
+    fn try_argsort_unstable(&self) -> OrderResult<Vec<usize>>
+    where
+        T: PartialOrd<T>,
+    {
+        self.try_argsort_unstable_by(ord_as_cmp)
+    }
+    #[cfg(feature = "std")]
+    /// Returns the permutation which would sort the slice by `compare`, not preserving the order of equal elements.
+    fn try_argsort_unstable_by<F>(&self, compare: F) -> OrderResult<Vec<usize>>
+    where
+        F: FnMut(&T, &T) -> Option<bool>;
+    #[cfg(feature = "std")]
+    /// Returns the permutation which would sort the slice by key, not preserving the order of equal elements.
+    ///
+    /// `f` is called once for each element.
+    fn try_argsort_unstable_by_key<K, F>(&self, f: F) -> OrderResult<Vec<usize>>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>;
+
+    #[inline]
+    /// [`PartialOrd`] version for [`slice::select_nth_unstable`]
+    ///
+    /// # Panics
+    ///
+    /// Panics when `index >= len()`, meaning it always panics on empty slices, the same as [`slice::select_nth_unstable`].
+    fn try_select_nth_unstable(&mut self, index: usize) -> OrderResult<(&mut [T], &mut T, &mut [T])>
+    where
+        T: PartialOrd<T>,
+    {
+        self.try_select_nth_unstable_by(index, ord_as_cmp)
+    }
+    /// [`PartialOrd`] version for [`slice::select_nth_unstable_by`]
+    ///
+    /// # Panics
+    ///
+    /// Panics when `index >= len()`, meaning it always panics on empty slices, the same as [`slice::select_nth_unstable`].
+    fn try_select_nth_unstable_by<F>(
+        &mut self,
+        index: usize,
+        compare: F,
+    ) -> OrderResult<(&mut [T], &mut T, &mut [T])>
+    where
+        F: FnMut(&T, &T) -> Option<bool>;
+    /// Version of [`slice::select_nth_unstable_by`] with the comparator which can fail with your own error.
+    ///
+    /// The error returned by `compare` is returned as is.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `index >= len()`, meaning it always panics on empty slices, the same as [`slice::select_nth_unstable`].
+    fn try_select_nth_unstable_by_result<E, F>(
+        &mut self,
+        index: usize,
+        compare: F,
+    ) -> Result<(&mut [T], &mut T, &mut [T]), E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>;
+    #[inline]
+    /// [`PartialOrd`] version for [`slice::select_nth_unstable_by_key`]
+    ///
+    /// # Panics
+    ///
+    /// Panics when `index >= len()`, meaning it always panics on empty slices, the same as [`slice::select_nth_unstable`].
+    fn try_select_nth_unstable_by_key<K, F>(
+        &mut self,
+        index: usize,
+        f: F,
+    ) -> OrderResult<(&mut [T], &mut T, &mut [T])>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>,
+    {
+        let mut f2 = f;
+        self.try_select_nth_unstable_by(index, |a, b| {
+            f2(a).partial_cmp(&f2(b)).map(|a| a == Ordering::Less)
+        })
+    }
+
     #[inline]
     /// [`PartialOrd`] version for [`slice::is_sorted`]
+    ///
+    /// Unlike [`slice::is_sorted`], equal adjacent elements are not in order.
+    /// Use [`try_is_sorted_by_partial_cmp`](`TrySort::try_is_sorted_by_partial_cmp`) to allow them.
     fn try_is_sorted(&self) -> OrderResult<bool>
     where
         T: PartialOrd<T>,
@@ -70,11 +349,32 @@ pub trait TrySort<T> {
         self.try_is_sorted_by(ord_as_cmp)
     }
     /// [`PartialOrd`] version for [`slice::is_sorted_by`]
+    ///
+    /// `compare` returns whether the two adjacent elements are in order.
     fn try_is_sorted_by<F>(&self, compare: F) -> OrderResult<bool>
     where
         F: FnMut(&T, &T) -> Option<bool>;
     #[inline]
+    /// [`PartialOrd`] version for [`slice::is_sorted_by`], taking the same comparator as [`try_min_by`](`crate::TryMinMax::try_min_by`).
+    ///
+    /// Equal adjacent elements are in order, the same as [`slice::is_sorted`].
+    fn try_is_sorted_by_partial_cmp<F>(&self, compare: F) -> OrderResult<bool>
+    where
+        F: FnMut(&T, &T) -> Option<Ordering>,
+    {
+        let mut compare = compare;
+        self.try_is_sorted_by(|a, b| compare(a, b).map(|o| o != Ordering::Greater))
+    }
+    /// Version of [`slice::is_sorted_by`] with the comparator which can fail with your own error.
+    ///
+    /// The error returned by `compare` is returned as is.
+    fn try_is_sorted_by_result<E, F>(&self, compare: F) -> Result<bool, E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>;
+    #[inline]
     /// [`PartialOrd`] version for [`slice::is_sorted_by_key`]
+    ///
+    /// Equal adjacent keys are not in order, the same as [`try_is_sorted`](`TrySort::try_is_sorted`).
     fn try_is_sorted_by_key<K, F>(&mut self, f: F) -> OrderResult<bool>
     where
         F: FnMut(&T) -> Option<K>,
@@ -92,7 +392,21 @@ impl<T> TrySort<T> for [T] {
     where
         F: FnMut(&T, &T) -> Option<bool>,
     {
-        std_mergesort::merge_sort(self, compare).ok_or(InvalidOrderError)
+        let mut compare = compare;
+        let mut tracker = FailureTracker::new(self);
+        let mut is_less = tracker.wrap(&mut compare);
+        self.try_sort_by_result(move |a, b| is_less(a, b).map(less_as_ordering))
+            .map_err(|()| tracker.error(self, &mut compare, OrderOperation::Sort))
+    }
+
+    #[inline]
+    #[cfg(feature = "std")]
+    fn try_sort_by_result<E, F>(&mut self, compare: F) -> Result<(), E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>,
+    {
+        let mut compare = compare;
+        std_mergesort::merge_sort(self, |a, b| compare(a, b).map(|o| o == Ordering::Less))
     }
 
     #[inline]
@@ -100,94 +414,332 @@ impl<T> TrySort<T> for [T] {
     where
         F: FnMut(&T, &T) -> Option<bool>,
     {
-        std_quicksort::quicksort(self, compare).ok_or(InvalidOrderError)
+        let mut compare = compare;
+        let mut tracker = FailureTracker::new(self);
+        let mut is_less = tracker.wrap(&mut compare);
+        self.try_sort_unstable_by_result(move |a, b| is_less(a, b).map(less_as_ordering))
+            .map_err(|()| tracker.error(self, &mut compare, OrderOperation::Sort))
     }
 
     #[inline]
-    fn try_is_sorted_by<F>(&self, compare: F) -> OrderResult<bool>
+    fn try_sort_unstable_by_result<E, F>(&mut self, compare: F) -> Result<(), E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>,
+    {
+        let mut compare = compare;
+        std_quicksort::quicksort(self, |a, b| compare(a, b).map(|o| o == Ordering::Less))
+    }
+
+    #[inline]
+    fn try_partial_sort_by<F>(&mut self, k: usize, compare: F) -> OrderResult<()>
     where
         F: FnMut(&T, &T) -> Option<bool>,
     {
-        try_is_sorted_by_slice(self, compare)
+        let mut compare = compare;
+        let mut tracker = FailureTracker::new(self);
+        let mut is_less = tracker.wrap(&mut compare);
+        self.try_partial_sort_by_result(k, move |a, b| is_less(a, b).map(less_as_ordering))
+            .map_err(|()| tracker.error(self, &mut compare, OrderOperation::Sort))
+    }
+
+    #[inline]
+    fn try_partial_sort_by_result<E, F>(&mut self, k: usize, compare: F) -> Result<(), E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>,
+    {
+        let mut compare = compare;
+        if k == 0 {
+            Ok(())
+        } else if k < self.len() {
+            // Move the smallest `k` elements to the front, then sort them.
+            self.try_select_nth_unstable_by_result(k - 1, &mut compare)?;
+            self[..k - 1].try_sort_unstable_by_result(compare)
+        } else {
+            self.try_sort_unstable_by_result(compare)
+        }
     }
 
     #[cfg(feature = "std")]
     #[inline]
-    fn try_sort_by_cached_key<K, F>(&mut self, f: F) -> OrderResult<()>
+    fn try_sort_atomic_by<F>(&mut self, compare: F) -> OrderResult<()>
+    where
+        F: FnMut(&T, &T) -> Option<bool>,
+    {
+        let indices = sort_indices(self, compare, true)?;
+        apply_permutation(self, indices);
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_sort_unstable_atomic_by<F>(&mut self, compare: F) -> OrderResult<()>
+    where
+        F: FnMut(&T, &T) -> Option<bool>,
+    {
+        let indices = sort_indices(self, compare, false)?;
+        apply_permutation(self, indices);
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_argsort_by<F>(&self, compare: F) -> OrderResult<Vec<usize>>
+    where
+        F: FnMut(&T, &T) -> Option<bool>,
+    {
+        sort_indices(self, compare, true)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_argsort_unstable_by<F>(&self, compare: F) -> OrderResult<Vec<usize>>
+    where
+        F: FnMut(&T, &T) -> Option<bool>,
+    {
+        sort_indices(self, compare, false)
+    }
+
+    #[cfg(feature = "std")]
+    fn try_argsort_by_key<K, F>(&self, f: F) -> OrderResult<Vec<usize>>
     where
         F: FnMut(&T) -> Option<K>,
         K: PartialOrd<K>,
     {
-        // Helper macro for indexing our vector by the smallest possible type, to reduce allocation.
-        macro_rules! sort_by_key {
-            ($t:ty, $slice:ident, $f:ident) => {{
-                let mut indices: Vec<_> = $slice
-                    .iter()
-                    .map($f)
-                    .enumerate()
-                    .map(|(i, k)| (k, i as $t))
-                    .collect();
-                // The elements of `indices` are unique, as they are indexed, so any sort will be
-                // stable with respect to the original slice. We use `sort_unstable` here because
-                // it requires less memory allocation.
-                indices.try_sort_unstable()?;
-                for i in 0..$slice.len() {
-                    let mut index = indices[i].1;
-                    while (index as usize) < i {
-                        index = indices[index as usize].1;
-                    }
-                    indices[i].1 = index;
-                    $slice.swap(i, index as usize);
-                }
-                Ok(())
-            }};
+        with_compact_index!(Option<K>, self.len(), I => {
+            let keys = sort_keys::<I, _, _, _>(self, f, true)?;
+            Ok(keys.into_iter().map(|(_, i)| i.to_usize()).collect())
+        })
+    }
+
+    #[cfg(feature = "std")]
+    fn try_argsort_unstable_by_key<K, F>(&self, f: F) -> OrderResult<Vec<usize>>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>,
+    {
+        with_compact_index!(Option<K>, self.len(), I => {
+            let keys = sort_keys::<I, _, _, _>(self, f, false)?;
+            Ok(keys.into_iter().map(|(_, i)| i.to_usize()).collect())
+        })
+    }
+
+    #[inline]
+    fn try_select_nth_unstable_by<F>(
+        &mut self,
+        index: usize,
+        compare: F,
+    ) -> OrderResult<(&mut [T], &mut T, &mut [T])>
+    where
+        F: FnMut(&T, &T) -> Option<bool>,
+    {
+        let mut compare = compare;
+        let mut tracker = FailureTracker::new(self);
+        let mut is_less = tracker.wrap(&mut compare);
+        // Only check for the failure first, because the returned parts borrow `self`.
+        if self
+            .try_select_nth_unstable_by_result(index, move |a, b| {
+                is_less(a, b).map(less_as_ordering)
+            })
+            .is_err()
+        {
+            return Err(tracker.error(self, &mut compare, OrderOperation::Select));
         }
+        let (left, right) = self.split_at_mut(index);
+        let (nth, right) = right.split_at_mut(1);
+        Ok((left, &mut nth[0], right))
+    }
+
+    #[inline]
+    fn try_select_nth_unstable_by_result<E, F>(
+        &mut self,
+        index: usize,
+        compare: F,
+    ) -> Result<(&mut [T], &mut T, &mut [T]), E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>,
+    {
+        let mut compare = compare;
+        std_quicksort::partition_at_index(self, index, |a, b| {
+            compare(a, b).map(|o| o == Ordering::Less)
+        })
+    }
+
+    #[inline]
+    fn try_is_sorted_by<F>(&self, compare: F) -> OrderResult<bool>
+    where
+        F: FnMut(&T, &T) -> Option<bool>,
+    {
+        let mut compare = compare;
+        let mut tracker = FailureTracker::new(self);
+        let mut in_order = tracker.wrap(&mut compare);
+        self.try_is_sorted_by_result(move |a, b| in_order(a, b).map(less_as_ordering))
+            .map_err(|()| tracker.error(self, &mut compare, OrderOperation::IsSorted))
+    }
 
-        let sz_u8 = core::mem::size_of::<(K, u8)>();
-        let sz_u16 = core::mem::size_of::<(K, u16)>();
-        let sz_u32 = core::mem::size_of::<(K, u32)>();
-        let sz_usize = core::mem::size_of::<(K, usize)>();
+    #[inline]
+    fn try_is_sorted_by_result<E, F>(&self, compare: F) -> Result<bool, E>
+    where
+        F: FnMut(&T, &T) -> Result<Ordering, E>,
+    {
+        let mut compare = compare;
+        for w in self.windows(2) {
+            if compare(&w[0], &w[1])? == Ordering::Greater {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
 
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_sort_by_cached_key<K, F>(&mut self, f: F) -> OrderResult<()>
+    where
+        F: FnMut(&T) -> Option<K>,
+        K: PartialOrd<K>,
+    {
         let len = self.len();
         if len < 2 {
             return Ok(());
         }
-        if sz_u8 < sz_u16 && len <= (u8::MAX as usize) {
-            return sort_by_key!(u8, self, f);
+        with_compact_index!(Option<K>, len, I => {
+            let mut keys = sort_keys::<I, _, _, _>(self, f, true)?;
+            for i in 0..len {
+                let mut index = keys[i].1.to_usize();
+                while index < i {
+                    index = keys[index].1.to_usize();
+                }
+                keys[i].1 = I::from_usize(index);
+                self.swap(i, index);
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Sorts the indices of `v`, comparing the elements they point to.
+///
+/// On error, the indices in the error are the positions in `v`, which is not modified.
+#[cfg(feature = "std")]
+fn sort_indices<T, F>(v: &[T], compare: F, stable: bool) -> OrderResult<Vec<usize>>
+where
+    F: FnMut(&T, &T) -> Option<bool>,
+{
+    let mut compare = compare;
+    let mut indices: Vec<usize> = (0..v.len()).collect();
+    let mut failed = (0, 0);
+    let mut is_less = |&a: &usize, &b: &usize| compare(&v[a], &v[b]).ok_or_else(|| failed = (a, b));
+    let sorted = if stable {
+        std_mergesort::merge_sort(&mut indices, &mut is_less)
+    } else {
+        std_quicksort::quicksort(&mut indices, &mut is_less)
+    };
+    match sorted {
+        Ok(()) => Ok(indices),
+        Err(()) => Err(InvalidOrderError::with_indices(
+            OrderOperation::Sort,
+            failed.0,
+            failed.1,
+        )),
+    }
+}
+
+/// Index types used by [`with_compact_index`], to reduce allocation.
+#[cfg(feature = "std")]
+trait CompactIndex: Copy {
+    fn from_usize(i: usize) -> Self;
+    fn to_usize(self) -> usize;
+}
+
+#[cfg(feature = "std")]
+macro_rules! impl_compact_index {
+    ($($t:ty),*) => {$(
+        impl CompactIndex for $t {
+            #[inline]
+            fn from_usize(i: usize) -> Self {
+                i as $t
+            }
+            #[inline]
+            fn to_usize(self) -> usize {
+                self as usize
+            }
         }
-        if sz_u16 < sz_u32 && len <= (u16::MAX as usize) {
-            return sort_by_key!(u16, self, f);
+    )*};
+}
+
+#[cfg(feature = "std")]
+impl_compact_index!(u8, u16, u32, usize);
+
+/// Sorts the keys of `v` paired with their indices, calling `f` once for each element.
+///
+/// On error, the indices in the error are the positions in `v`.
+#[cfg(feature = "std")]
+fn sort_keys<I, T, K, F>(v: &[T], f: F, stable: bool) -> OrderResult<Vec<(Option<K>, I)>>
+where
+    I: CompactIndex,
+    F: FnMut(&T) -> Option<K>,
+    K: PartialOrd<K>,
+{
+    let mut keys: Vec<_> = v
+        .iter()
+        .map(f)
+        .enumerate()
+        .map(|(i, k)| (k, I::from_usize(i)))
+        .collect();
+    // The indices are unique, so comparing them after the keys makes any sort stable with respect to
+    // the original slice. We use `quicksort` here because it requires less memory allocation.
+    let mut failed = (0, 0);
+    let mut is_less = |a: &(Option<K>, I), b: &(Option<K>, I)| match a.0.partial_cmp(&b.0) {
+        Some(Ordering::Less) => Ok(true),
+        Some(Ordering::Greater) => Ok(false),
+        Some(Ordering::Equal) => Ok(stable && a.1.to_usize() < b.1.to_usize()),
+        None => {
+            failed = (a.1.to_usize(), b.1.to_usize());
+            Err(())
         }
-        if sz_u32 < sz_usize && len <= (u32::MAX as usize) {
-            return sort_by_key!(u32, self, f);
+    };
+    match std_quicksort::quicksort(&mut keys, &mut is_less) {
+        Ok(()) => Ok(keys),
+        Err(()) => Err(InvalidOrderError::with_indices(
+            OrderOperation::Sort,
+            failed.0,
+            failed.1,
+        )),
+    }
+}
+
+/// Moves the elements of `v` so that `v[i]` becomes the element which was at `indices[i]`.
+///
+/// `indices` must be a permutation of `0..v.len()`.
+#[cfg(feature = "std")]
+fn apply_permutation<T>(v: &mut [T], mut indices: Vec<usize>) {
+    for i in 0..v.len() {
+        let mut index = indices[i];
+        while index < i {
+            index = indices[index];
         }
-        sort_by_key!(usize, self, f)
+        indices[i] = index;
+        v.swap(i, index);
     }
 }
 
 /// Function to check whether slice is sorted.
+#[inline]
 pub fn try_is_sorted_by_slice<T, F>(slice: &[T], compare: F) -> OrderResult<bool>
 where
     F: FnMut(&T, &T) -> Option<bool>,
 {
-    let mut cmp = compare;
-    if slice.len() > 1 {
-        unsafe {
-            let mut prev = slice.get_unchecked(0);
-            for i in 1..slice.len() {
-                let next = slice.get_unchecked(i);
-                if let Some(x) = cmp(&prev, &next) {
-                    if !x {
-                        return Ok(false);
-                    }
-                    prev = next;
-                } else {
-                    return Err(InvalidOrderError);
-                }
-            }
-        }
+    slice.try_is_sorted_by(compare)
+}
+
+/// Converts the result of `is_less` to [`Ordering`] for the `_by_result` methods, which only check for [`Ordering::Less`]
+/// or [`Ordering::Greater`].
+#[inline]
+fn less_as_ordering(less: bool) -> Ordering {
+    if less {
+        Ordering::Less
+    } else {
+        Ordering::Greater
     }
-    Ok(true)
 }
 
 /// Function to check whether iter is sorted.
@@ -198,24 +750,102 @@ where
 {
     let mut cmp = compare;
     if let Some(mut prev) = iter.next() {
-        for next in iter {
+        for (i, next) in iter.enumerate() {
             if let Some(x) = cmp(&prev, &next) {
                 if !x {
                     return Ok(false);
                 }
                 prev = next;
             } else {
-                return Err(InvalidOrderError);
+                return Err(InvalidOrderError::with_indices(
+                    OrderOperation::IsSorted,
+                    i,
+                    i + 1,
+                ));
             }
         }
     }
     Ok(true)
 }
 
+/// Remembers which elements of a slice were passed to the comparison which returned [`None`].
+///
+/// The sort algorithms copy some elements out of the slice (like the pivot), so only the elements
+/// which are inside the slice at the time of the failure have a known index.
+pub(crate) struct FailureTracker<T> {
+    start: *const T,
+    len: usize,
+    failed: Option<(*const T, *const T)>,
+}
+
+impl<T> FailureTracker<T> {
+    pub(crate) fn new(v: &[T]) -> Self {
+        FailureTracker {
+            start: v.as_ptr(),
+            len: v.len(),
+            failed: None,
+        }
+    }
+
+    /// Wraps `compare` to record the elements when it returns [`None`].
+    pub(crate) fn wrap<'a, R, F>(
+        &'a mut self,
+        compare: &'a mut F,
+    ) -> impl FnMut(&T, &T) -> Result<R, ()> + 'a
+    where
+        F: FnMut(&T, &T) -> Option<R>,
+    {
+        move |a, b| compare(a, b).ok_or_else(|| self.failed = Some((a, b)))
+    }
+
+    fn index_of(&self, p: *const T) -> Option<usize> {
+        let size = core::mem::size_of::<T>();
+        let offset = (p as usize).wrapping_sub(self.start as usize);
+        if size == 0 || offset / size >= self.len {
+            None
+        } else {
+            Some(offset / size)
+        }
+    }
+
+    /// Creates the error from the recorded failure.
+    ///
+    /// If only one of the elements is known, this searches `v` for another element which cannot be
+    /// compared with it, so the error can report both indices.
+    pub(crate) fn error<R, F>(
+        &self,
+        v: &[T],
+        compare: &mut F,
+        operation: OrderOperation,
+    ) -> InvalidOrderError
+    where
+        F: FnMut(&T, &T) -> Option<R>,
+    {
+        let (a, b) = match self.failed {
+            Some((a, b)) => (self.index_of(a), self.index_of(b)),
+            None => (None, None),
+        };
+        match (a, b) {
+            (Some(i), Some(j)) => InvalidOrderError::with_indices(operation, i, j),
+            (Some(i), None) | (None, Some(i)) => {
+                let partner = (0..v.len()).find(|&j| {
+                    j != i && (compare(&v[i], &v[j]).is_none() || compare(&v[j], &v[i]).is_none())
+                });
+                match partner {
+                    Some(j) => InvalidOrderError::with_indices(operation, i, j),
+                    None => InvalidOrderError::with_index(operation, i),
+                }
+            }
+            (None, None) => InvalidOrderError::new(operation),
+        }
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "std")]
 mod tests {
     use crate::sort::*;
+    use crate::OrderOperation;
     use rand::distributions::Standard;
     use rand::prelude::*;
     use std::vec::Vec;
@@ -236,6 +866,167 @@ mod tests {
         v.push(f32::NAN);
         let res = v.try_sort();
         assert!(res.is_err());
-        assert!(!v.try_is_sorted().is_err())
+        assert!(v.try_is_sorted().is_ok())
+    }
+
+    #[test]
+    fn try_select_nth_unstable_ok() {
+        let rng = thread_rng();
+        let mut v: Vec<f32> = Standard.sample_iter(rng).take(100).collect();
+        let mut sorted = v.clone();
+        sorted.try_sort().unwrap();
+        for &i in &[0, 1, 37, 50, 98, 99] {
+            let (left, nth, right) = v.try_select_nth_unstable(i).unwrap();
+            assert_eq!(*nth, sorted[i]);
+            assert!(left.iter().all(|x| x <= nth));
+            assert!(right.iter().all(|x| x >= nth));
+        }
+    }
+
+    #[test]
+    fn try_select_nth_unstable_error() {
+        let rng = thread_rng();
+        let mut v: Vec<f32> = Standard.sample_iter(rng).take(100).collect();
+        v.push(f32::NAN);
+        assert!(v.try_select_nth_unstable(0).is_err());
+        assert!(v.try_select_nth_unstable(50).is_err());
+        assert!(v.try_select_nth_unstable(100).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn try_select_nth_unstable_out_of_bounds() {
+        let _ = [1.0, 2.0].try_select_nth_unstable(5);
+    }
+
+    #[test]
+    fn try_sort_error_indices() {
+        let rng = thread_rng();
+        let mut v: Vec<f32> = Standard.sample_iter(rng).take(1000).collect();
+        v[500] = f32::NAN;
+        let mut v2 = v.clone();
+
+        let err = v.try_sort().unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::Sort);
+        let (i, j) = err.indices().unwrap();
+        assert!(v[i].is_nan() || v[j].is_nan());
+
+        let err = v2.try_sort_unstable().unwrap_err();
+        let (i, j) = err.indices().unwrap();
+        assert!(v2[i].is_nan() || v2[j].is_nan());
+
+        let err = v2.try_select_nth_unstable(300).unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::Select);
+        let (i, j) = err.indices().unwrap();
+        assert!(v2[i].is_nan() || v2[j].is_nan());
+    }
+
+    #[test]
+    fn try_sort_atomic() {
+        let rng = thread_rng();
+        let mut v: Vec<f32> = Standard.sample_iter(rng).take(100).collect();
+        let mut sorted = v.clone();
+        sorted.try_sort().unwrap();
+        let mut unstable = v.clone();
+        assert!(v.try_sort_atomic().is_ok());
+        assert_eq!(v, sorted);
+        assert!(unstable.try_sort_unstable_atomic().is_ok());
+        assert_eq!(unstable, sorted);
+
+        v.reverse();
+        v.insert(30, f32::NAN);
+        let original = v.clone();
+        let err = v.try_sort_atomic().unwrap_err();
+        let (i, j) = err.indices().unwrap();
+        assert!(v[i].is_nan() || v[j].is_nan());
+        assert!(v
+            .iter()
+            .zip(&original)
+            .all(|(a, b)| a.to_bits() == b.to_bits()));
+        assert!(v.try_sort_unstable_atomic().is_err());
+        assert!(v
+            .iter()
+            .zip(&original)
+            .all(|(a, b)| a.to_bits() == b.to_bits()));
+    }
+
+    #[test]
+    fn try_sort_unstable_by_result() {
+        let rng = thread_rng();
+        let mut v: Vec<u32> = Standard.sample_iter(rng).take(100).collect();
+        let checked = |a: &u32, b: &u32| if *a == 7 { Err(*b) } else { Ok(a.cmp(b)) };
+        assert_eq!(v.try_sort_unstable_by_result(checked), Ok(()));
+        assert!(v.try_is_sorted_by_result(checked).unwrap());
+        v.push(7);
+        assert!(v.try_sort_unstable_by_result(checked).is_err());
+        assert!(v.try_select_nth_unstable_by_result(50, checked).is_err());
+    }
+
+    #[test]
+    fn try_is_sorted_duplicates() {
+        let mut v = [1.0, 2.0, 2.0, 3.0];
+        assert_eq!(v.try_is_sorted(), Ok(false));
+        assert_eq!(v.try_is_sorted_by_key(|x| Some(*x)), Ok(false));
+        assert_eq!(
+            v.try_is_sorted_by_partial_cmp(|a, b| a.partial_cmp(b)),
+            Ok(true)
+        );
+        assert_eq!(v.try_is_sorted_by_key(|x| Some(-x)), Ok(false));
+        assert_eq!(
+            v.try_sort_unstable_by_partial_cmp(|a, b| b.partial_cmp(a)),
+            Ok(())
+        );
+        assert_eq!(v, [3.0, 2.0, 2.0, 1.0]);
+        assert_eq!(
+            v.try_is_sorted_by_partial_cmp(|a, b| b.partial_cmp(a)),
+            Ok(true)
+        );
+        assert_eq!(v.try_is_sorted(), Ok(false));
+    }
+
+    #[test]
+    fn try_partial_sort() {
+        let rng = thread_rng();
+        let mut v: Vec<f32> = Standard.sample_iter(rng).take(1000).collect();
+        let mut sorted = v.clone();
+        sorted.try_sort().unwrap();
+        assert!(v.try_partial_sort(100).is_ok());
+        assert_eq!(&v[..100], &sorted[..100]);
+        assert!(v.try_partial_sort(2000).is_ok());
+        assert_eq!(v, sorted);
+
+        v.push(f32::NAN);
+        assert!(v.try_partial_sort(10).is_err());
+    }
+
+    #[test]
+    fn try_argsort() {
+        let rng = thread_rng();
+        let v: Vec<f32> = Standard.sample_iter(rng).take(300).collect();
+        let mut sorted = v.clone();
+        sorted.try_sort().unwrap();
+        for indices in [
+            v.try_argsort().unwrap(),
+            v.try_argsort_unstable().unwrap(),
+            v.try_argsort_by_key(|x| Some(*x)).unwrap(),
+            v.try_argsort_unstable_by_key(|x| Some(*x)).unwrap(),
+        ] {
+            let reordered: Vec<_> = indices.iter().map(|&i| v[i]).collect();
+            assert_eq!(reordered, sorted);
+        }
+
+        let v = [(1, 2.0), (0, 1.0), (2, 2.0), (3, 1.0)];
+        assert_eq!(v.try_argsort_by_key(|x| Some(x.1)), Ok(vec![1, 3, 0, 2]));
+        assert_eq!(
+            v.try_argsort_by(|a, b| a.1.partial_cmp(&b.1).map(|o| o == Ordering::Less)),
+            Ok(vec![1, 3, 0, 2])
+        );
+
+        let v = [1.0, 2.0, f64::NAN, 0.0];
+        let err = v.try_argsort_by_key(|x| Some(*x)).unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::Sort);
+        let (i, j) = err.indices().unwrap();
+        assert!(i == 2 || j == 2);
+        assert!(v.try_argsort().is_err());
     }
 }
@@ -4,9 +4,9 @@ use std::{mem, ptr};
 /// Inserts `v[0]` into pre-sorted sequence `v[1..]` so that whole `v[..]` becomes sorted.
 ///
 /// This is the integral subroutine of insertion sort.
-fn insert_head<T, F>(v: &mut [T], is_less: &mut F) -> Option<()>
+fn insert_head<T, E, F>(v: &mut [T], is_less: &mut F) -> Result<(), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     if v.len() >= 2 && is_less(&v[1], &v[0])? {
         unsafe {
@@ -68,7 +68,7 @@ where
             }
         }
     }
-    Some(())
+    Ok(())
 }
 
 /// Merges non-decreasing runs `v[..mid]` and `v[mid..]` using `buf` as temporary storage, and
@@ -78,9 +78,9 @@ where
 ///
 /// The two slices must be non-empty and `mid` must be in bounds. Buffer `buf` must be long enough
 /// to hold a copy of the shorter slice. Also, `T` must not be a zero-sized type.
-unsafe fn merge<T, F>(v: &mut [T], mid: usize, buf: *mut T, is_less: &mut F) -> Option<()>
+unsafe fn merge<T, E, F>(v: &mut [T], mid: usize, buf: *mut T, is_less: &mut F) -> Result<(), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     let len = v.len();
     let v = v.as_mut_ptr();
@@ -188,7 +188,7 @@ where
         }
     }
 
-    Some(())
+    Ok(())
 }
 
 /// This merge sort borrows some (but not all) ideas from TimSort, which is described in detail
@@ -203,9 +203,9 @@ where
 /// 2. for every `i` in `2..runs.len()`: `runs[i - 2].len > runs[i - 1].len + runs[i].len`
 ///
 /// The invariants ensure that the total running time is `O(n * log(n))` worst-case.
-pub fn merge_sort<T, F>(v: &mut [T], mut is_less: F) -> Option<()>
+pub fn merge_sort<T, E, F>(v: &mut [T], mut is_less: F) -> Result<(), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     // Slices of up to this length get sorted using insertion sort.
     const MAX_INSERTION: usize = 20;
@@ -214,7 +214,7 @@ where
 
     // Sorting has no meaningful behavior on zero-sized types.
     if mem::size_of::<T>() == 0 {
-        return Some(());
+        return Ok(());
     }
 
     let len = v.len();
@@ -223,10 +223,10 @@ where
     if len <= MAX_INSERTION {
         if len >= 2 {
             for i in (0..len - 1).rev() {
-                insert_head(&mut v[i..], &mut is_less);
+                insert_head(&mut v[i..], &mut is_less)?;
             }
         }
-        return Some(());
+        return Ok(());
     }
 
     // Allocate a buffer to use as scratch memory. We keep the length 0 so we can keep in it
@@ -266,7 +266,7 @@ where
         // merge sort on short sequences, so this significantly improves performance.
         while start > 0 && end - start < MIN_RUN {
             start -= 1;
-            insert_head(&mut v[start..end], &mut is_less);
+            insert_head(&mut v[start..end], &mut is_less)?;
         }
 
         // Push this run onto the stack.
@@ -286,7 +286,7 @@ where
                     left.len,
                     buf.as_mut_ptr(),
                     &mut is_less,
-                );
+                )?;
             }
             runs[r] = Run {
                 start: left.start,
@@ -338,5 +338,5 @@ where
         len: usize,
     }
 
-    Some(())
+    Ok(())
 }
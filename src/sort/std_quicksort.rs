@@ -28,9 +28,9 @@ impl<T> Drop for CopyOnDrop<T> {
 }
 
 /// Shifts the first element to the right until it encounters a greater or equal element.
-fn shift_head<T, F>(v: &mut [T], is_less: &mut F) -> Option<()>
+fn shift_head<T, E, F>(v: &mut [T], is_less: &mut F) -> Result<(), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     let len = v.len();
     // SAFETY: The unsafe operations below involves indexing without a bound check (`get_unchecked` and `get_unchecked_mut`)
@@ -73,14 +73,14 @@ where
             // `hole` gets dropped and thus copies `tmp` into the remaining hole in `v`.
         }
 
-        Some(())
+        Ok(())
     }
 }
 
 /// Shifts the last element to the left until it encounters a smaller or equal element.
-fn shift_tail<T, F>(v: &mut [T], is_less: &mut F) -> Option<()>
+fn shift_tail<T, E, F>(v: &mut [T], is_less: &mut F) -> Result<(), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     let len = v.len();
     // SAFETY: The unsafe operations below involves indexing without a bound check (`get_unchecked` and `get_unchecked_mut`)
@@ -122,7 +122,7 @@ where
             }
             // `hole` gets dropped and thus copies `tmp` into the remaining hole in `v`.
         }
-        Some(())
+        Ok(())
     }
 }
 
@@ -130,9 +130,9 @@ where
 ///
 /// Returns `true` if the slice is sorted at the end. This function is *O*(*n*) worst-case.
 #[cold]
-fn partial_insertion_sort<T, F>(v: &mut [T], is_less: &mut F) -> Option<bool>
+fn partial_insertion_sort<T, E, F>(v: &mut [T], is_less: &mut F) -> Result<bool, E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     // Maximum number of adjacent out-of-order pairs that will get shifted.
     const MAX_STEPS: usize = 5;
@@ -154,90 +154,96 @@ where
 
         // Are we done?
         if i == len {
-            return Some(true);
+            return Ok(true);
         }
 
         // Don't shift elements on short arrays, that has a performance cost.
         if len < SHORTEST_SHIFTING {
-            return Some(false);
+            return Ok(false);
         }
 
         // Swap the found pair of elements. This puts them in correct order.
         v.swap(i - 1, i);
 
         // Shift the smaller element to the left.
-        shift_tail(&mut v[..i], is_less);
+        shift_tail(&mut v[..i], is_less)?;
         // Shift the greater element to the right.
-        shift_head(&mut v[i..], is_less);
+        shift_head(&mut v[i..], is_less)?;
     }
 
     // Didn't manage to sort the slice in the limited number of steps.
-    Some(false)
+    Ok(false)
 }
 
 /// Sorts a slice using insertion sort, which is *O*(*n*^2) worst-case.
-fn insertion_sort<T, F>(v: &mut [T], is_less: &mut F) -> Option<()>
+fn insertion_sort<T, E, F>(v: &mut [T], is_less: &mut F) -> Result<(), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     for i in 1..v.len() {
         shift_tail(&mut v[..i + 1], is_less)?;
     }
 
-    Some(())
+    Ok(())
 }
 
-/// Sorts `v` using heapsort, which guarantees *O*(*n* \* log(*n*)) worst-case.
-#[cold]
-pub fn heapsort<T, F>(v: &mut [T], mut is_less: F) -> Option<()>
+/// Sifts `v[node]` down so that `v` respects the binary heap invariant `parent >= child`.
+///
+/// The children of `node` must already respect the invariant.
+pub fn sift_down<T, E, F>(v: &mut [T], mut node: usize, is_less: &mut F) -> Result<(), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
-    // This binary heap respects the invariant `parent >= child`.
-    let mut sift_down = |v: &mut [T], mut node: usize| {
-        loop {
-            // Children of `node`:
-            let left = 2 * node + 1;
-            let right = 2 * node + 2;
-
-            // Choose the greater child.
-            let greater = if right < v.len() {
-                if let Some(lt) = is_less(&v[left], &v[right]) {
-                    if lt {
-                        right
-                    } else {
-                        left
-                    }
-                } else {
-                    return None;
-                }
-            } else {
-                left
-            };
+    loop {
+        // Children of `node`:
+        let left = 2 * node + 1;
+        let right = 2 * node + 2;
 
-            // Stop if the invariant holds at `node`.
-            if greater >= v.len() || !is_less(&v[node], &v[greater])? {
-                break Some(());
-            }
+        // Choose the greater child.
+        let greater = if right < v.len() && is_less(&v[left], &v[right])? {
+            right
+        } else {
+            left
+        };
 
-            // Swap `node` with the greater child, move one step down, and continue sifting.
-            v.swap(node, greater);
-            node = greater;
+        // Stop if the invariant holds at `node`.
+        if greater >= v.len() || !is_less(&v[node], &v[greater])? {
+            return Ok(());
         }
-    };
 
-    // Build the heap in linear time.
+        // Swap `node` with the greater child, move one step down, and continue sifting.
+        v.swap(node, greater);
+        node = greater;
+    }
+}
+
+/// Builds the binary heap which respects the invariant `parent >= child` in linear time.
+pub fn heapify<T, E, F>(v: &mut [T], is_less: &mut F) -> Result<(), E>
+where
+    F: FnMut(&T, &T) -> Result<bool, E>,
+{
     for i in (0..v.len() / 2).rev() {
-        sift_down(v, i)?;
+        sift_down(v, i, is_less)?;
     }
+    Ok(())
+}
+
+/// Sorts `v` using heapsort, which guarantees *O*(*n* \* log(*n*)) worst-case.
+#[cold]
+pub fn heapsort<T, E, F>(v: &mut [T], mut is_less: F) -> Result<(), E>
+where
+    F: FnMut(&T, &T) -> Result<bool, E>,
+{
+    // This binary heap respects the invariant `parent >= child`.
+    heapify(v, &mut is_less)?;
 
     // Pop maximal elements from the heap.
     for i in (1..v.len()).rev() {
         v.swap(0, i);
-        sift_down(&mut v[..i], 0);
+        sift_down(&mut v[..i], 0, &mut is_less)?;
     }
 
-    Some(())
+    Ok(())
 }
 
 /// Partitions `v` into elements smaller than `pivot`, followed by elements greater than or equal
@@ -249,9 +255,9 @@ where
 /// This idea is presented in the [BlockQuicksort][pdf] paper.
 ///
 /// [pdf]: http://drops.dagstuhl.de/opus/volltexte/2016/6389/pdf/LIPIcs-ESA-2016-38.pdf
-fn partition_in_blocks<T, F>(v: &mut [T], pivot: &T, is_less: &mut F) -> Option<usize>
+fn partition_in_blocks<T, E, F>(v: &mut [T], pivot: &T, is_less: &mut F) -> Result<usize, E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     // Number of elements in a typical block.
     const BLOCK: usize = 128;
@@ -414,12 +420,12 @@ where
 
         if start_l == end_l {
             // All out-of-order elements in the left block were moved. Move to the next block.
-            l = unsafe { l.offset(block_l as isize) };
+            l = unsafe { l.add(block_l) };
         }
 
         if start_r == end_r {
             // All out-of-order elements in the right block were moved. Move to the previous block.
-            r = unsafe { r.offset(-(block_r as isize)) };
+            r = unsafe { r.sub(block_r) };
         }
 
         if is_done {
@@ -442,7 +448,7 @@ where
                 r = r.offset(-1);
             }
         }
-        Some(width(v.as_mut_ptr(), r))
+        Ok(width(v.as_mut_ptr(), r))
     } else if start_r < end_r {
         // The right block remains.
         // Move its remaining out-of-order elements to the far left.
@@ -454,10 +460,10 @@ where
                 l = l.offset(1);
             }
         }
-        Some(width(v.as_mut_ptr(), l))
+        Ok(width(v.as_mut_ptr(), l))
     } else {
         // Nothing else to do, we're done.
-        Some(width(v.as_mut_ptr(), l))
+        Ok(width(v.as_mut_ptr(), l))
     }
 }
 
@@ -468,9 +474,9 @@ where
 ///
 /// 1. Number of elements smaller than `v[pivot]`.
 /// 2. True if `v` was already partitioned.
-fn partition<T, F>(v: &mut [T], pivot: usize, is_less: &mut F) -> Option<(usize, bool)>
+fn partition<T, E, F>(v: &mut [T], pivot: usize, is_less: &mut F) -> Result<(usize, bool), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     let (mid, was_partitioned) = {
         // Place the pivot at the beginning of slice.
@@ -520,16 +526,16 @@ where
     // Place the pivot between the two partitions.
     v.swap(0, mid);
 
-    Some((mid, was_partitioned))
+    Ok((mid, was_partitioned))
 }
 
 /// Partitions `v` into elements equal to `v[pivot]` followed by elements greater than `v[pivot]`.
 ///
 /// Returns the number of elements equal to the pivot. It is assumed that `v` does not contain
 /// elements smaller than the pivot.
-fn partition_equal<T, F>(v: &mut [T], pivot: usize, is_less: &mut F) -> Option<usize>
+fn partition_equal<T, E, F>(v: &mut [T], pivot: usize, is_less: &mut F) -> Result<usize, E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     // Place the pivot at the beginning of slice.
     v.swap(0, pivot);
@@ -578,7 +584,7 @@ where
     }
 
     // We found `l` elements equal to the pivot. Add 1 to account for the pivot itself.
-    Some(l + 1)
+    Ok(l + 1)
 
     // `_pivot_guard` goes out of scope and writes the pivot (which is a stack-allocated variable)
     // back into the slice where it originally was. This step is critical in ensuring safety!
@@ -632,9 +638,9 @@ fn break_patterns<T>(v: &mut [T]) {
 /// Chooses a pivot in `v` and returns the index and `true` if the slice is likely already sorted.
 ///
 /// Elements in `v` might be reordered in the process.
-fn choose_pivot<T, F>(v: &mut [T], is_less: &mut F) -> Option<(usize, bool)>
+fn choose_pivot<T, E, F>(v: &mut [T], is_less: &mut F) -> Result<(usize, bool), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     // Minimum length to choose the median-of-medians method.
     // Shorter slices use the simple median-of-three method.
@@ -645,7 +651,7 @@ where
     let len = v.len();
 
     // Three indices near which we are going to choose a pivot.
-    let mut a = len / 4 * 1;
+    let mut a = len / 4;
     let mut b = len / 4 * 2;
     let mut c = len / 4 * 3;
 
@@ -659,7 +665,7 @@ where
                 ptr::swap(a, b);
                 swaps += 1;
             }
-            Some(())
+            Ok(())
         };
 
         // Swaps indices so that `v[a] <= v[b] <= v[c]`.
@@ -684,7 +690,7 @@ where
         sort3(&mut a, &mut b, &mut c)?;
     }
 
-    Some(if swaps < MAX_SWAPS {
+    Ok(if swaps < MAX_SWAPS {
         (b, swaps == 0)
     } else {
         // The maximum number of swaps was performed. Chances are the slice is descending or mostly
@@ -700,14 +706,14 @@ where
 ///
 /// `limit` is the number of allowed imbalanced partitions before switching to `heapsort`. If zero,
 /// this function will immediately switch to heapsort.
-fn recurse<'a, T, F>(
+fn recurse<'a, T, E, F>(
     mut v: &'a mut [T],
     is_less: &mut F,
     mut pred: Option<&'a T>,
     mut limit: u32,
-) -> Option<()>
+) -> Result<(), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     // Slices of up to this length get sorted using insertion sort.
     const MAX_INSERTION: usize = 20;
@@ -722,15 +728,15 @@ where
 
         // Very short slices get sorted using insertion sort.
         if len <= MAX_INSERTION {
-            insertion_sort(v, is_less);
-            return Some(());
+            insertion_sort(v, is_less)?;
+            return Ok(());
         }
 
         // If too many bad pivot choices were made, simply fall back to heapsort in order to
         // guarantee `O(n * log(n))` worst-case.
         if limit == 0 {
-            heapsort(v, is_less);
-            return Some(());
+            heapsort(v, is_less)?;
+            return Ok(());
         }
 
         // If the last partitioning was imbalanced, try breaking patterns in the slice by shuffling
@@ -749,7 +755,7 @@ where
             // Try identifying several out-of-order elements and shifting them to correct
             // positions. If the slice ends up being completely sorted, we're done.
             if partial_insertion_sort(v, is_less)? {
-                return Some(());
+                return Ok(());
             }
         }
 
@@ -780,24 +786,24 @@ where
         // calls and consume less stack space. Then just continue with the longer side (this is
         // akin to tail recursion).
         if left.len() < right.len() {
-            recurse(left, is_less, pred, limit);
+            recurse(left, is_less, pred, limit)?;
             v = right;
             pred = Some(pivot);
         } else {
-            recurse(right, is_less, Some(pivot), limit);
+            recurse(right, is_less, Some(pivot), limit)?;
             v = left;
         }
     }
 }
 
 /// Sorts `v` using pattern-defeating quicksort, which is *O*(*n* \* log(*n*)) worst-case.
-pub fn quicksort<T, F>(v: &mut [T], mut is_less: F) -> Option<()>
+pub fn quicksort<T, E, F>(v: &mut [T], mut is_less: F) -> Result<(), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     // Sorting has no meaningful behavior on zero-sized types.
     if mem::size_of::<T>() == 0 {
-        return Some(());
+        return Ok(());
     }
 
     // Limit the number of imbalanced partitions to `floor(log2(len)) + 1`.
@@ -806,22 +812,21 @@ where
     recurse(v, &mut is_less, None, limit)
 }
 
-/*
-fn partition_at_index_loop<'a, T, F>(
+fn partition_at_index_loop<'a, T, E, F>(
     mut v: &'a mut [T],
     mut index: usize,
     is_less: &mut F,
     mut pred: Option<&'a T>,
-) -> Option<()>
+) -> Result<(), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
     loop {
         // For slices of up to this length it's probably faster to simply sort them.
         const MAX_INSERTION: usize = 10;
         if v.len() <= MAX_INSERTION {
-            insertion_sort(v, is_less);
-            return Some(());
+            insertion_sort(v, is_less)?;
+            return Ok(());
         }
 
         // Choose a pivot
@@ -836,12 +841,12 @@ where
 
                 // If we've passed our index, then we're good.
                 if mid > index {
-                    return Some(());
+                    return Ok(());
                 }
 
                 // Otherwise, continue sorting elements greater than the pivot.
-                v = &mut v[mid..];
-                index = index - mid;
+                v = &mut { v }[mid..];
+                index -= mid;
                 pred = None;
                 continue;
             }
@@ -863,22 +868,23 @@ where
         } else {
             // If mid == index, then we're done, since partition() guaranteed that all elements
             // after mid are greater than or equal to mid.
-            return Some(());
+            return Ok(());
         }
     }
 }
 
-pub fn partition_at_index<T, F>(
+/// Reorders `v` such that the element at `index` is at its final sorted position.
+///
+/// Returns the elements before `index`, the element at `index` and the elements after `index`.
+/// Panics when `index >= v.len()`, the same as [`slice::select_nth_unstable`].
+pub fn partition_at_index<T, E, F>(
     v: &mut [T],
     index: usize,
     mut is_less: F,
-) -> Option<(&mut [T], &mut T, &mut [T])>
+) -> Result<(&mut [T], &mut T, &mut [T]), E>
 where
-    F: FnMut(&T, &T) -> Option<bool>,
+    F: FnMut(&T, &T) -> Result<bool, E>,
 {
-    //use cmp::Ordering::Greater;
-    //use cmp::Ordering::Less;
-
     if index >= v.len() {
         panic!(
             "partition_at_index index {} greater than length of slice {}",
@@ -890,48 +896,29 @@ where
     if mem::size_of::<T>() == 0 {
         // Sorting has no meaningful behavior on zero-sized types. Do nothing.
     } else if index == v.len() - 1 {
-        // Find max element and place it in the last position of the array. We're free to use
-        // `unwrap()` here because we know v must not be empty.
-        if let Some((max_index, _)) = v
-            .iter()
-            .enumerate()
-            .try_fold(None, |max: Option<(usize, &T)>, next| match (max, next) {
-                (None, n) => Some(Some(n)),
-                (Some(m), n) if !is_less(m.1, n.1)? => Some(Some(n)),
-                (m, _) => Some(m),
-            })
-            //.max_by(|&(_, x), &(_, y)| match is_less(x, y) { None => None, Some(true) => Less , Some(false) => Greater })
-            .flatten()
-        {
-            v.swap(max_index, index)
-        } else {
-            return None;
+        // Find max element and place it in the last position of the array.
+        let mut max_index = 0;
+        for i in 1..v.len() {
+            if !is_less(&v[i], &v[max_index])? {
+                max_index = i;
+            }
         }
+        v.swap(max_index, index);
     } else if index == 0 {
-        // Find min element and place it in the first position of the array. We're free to use
-        // `unwrap()` here because we know v must not be empty.
-        if let Some((min_index, _)) = v
-            .iter()
-            .enumerate()
-            .try_fold(None, |min: Option<(usize, &T)>, next| match (min, next) {
-                (None, n) => Some(Some(n)),
-                (Some(m), n) if is_less(m.1, n.1)? => Some(Some(n)),
-                (m, _) => Some(m),
-            })
-            //.min_by(|&(_, x), &(_, y)| if is_less(x, y) { Less } else { Greater })
-            .flatten()
-        {
-            v.swap(min_index, index)
-        } else {
-            return None;
+        // Find min element and place it in the first position of the array.
+        let mut min_index = 0;
+        for i in 1..v.len() {
+            if is_less(&v[i], &v[min_index])? {
+                min_index = i;
+            }
         }
+        v.swap(min_index, index);
     } else {
-        partition_at_index_loop(v, index, &mut is_less, None);
+        partition_at_index_loop(v, index, &mut is_less, None)?;
     }
 
     let (left, right) = v.split_at_mut(index);
     let (pivot, right) = right.split_at_mut(1);
     let pivot = &mut pivot[0];
-    Some((left, pivot, right))
+    Ok((left, pivot, right))
 }
-*/
@@ -0,0 +1,304 @@
+use crate::binary_search::try_range_indices;
+use crate::{
+    check_comparable, InvalidOrderError, OrderOperation, OrderResult, TryBinarySearch, TrySort,
+};
+#[cfg(feature = "std")]
+use core::cmp::Ordering;
+use core::ops::{Deref, DerefMut, RangeBounds};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// Slice or [`Vec`] which is known to be sorted, made by a successful sort or check.
+///
+/// Methods relying on the order do not check it again, but they still return error when the given value cannot be compared with the elements.
+/// The elements can be read through [`Deref`], but cannot be modified in a way which breaks the order.
+///
+/// The order is only checked when this is made, so it is a logic invariant, not a safety one.
+/// Elements with interior mutability, like [`Cell`](`core::cell::Cell`), can still be reordered afterwards,
+/// and then the methods return unspecified results, though never undefined behavior.
+/// ```
+/// use try_partialord::*;
+///
+/// let mut v = vec![3.0, 1.0, 2.0, 2.0];
+/// let sorted = Sorted::try_sort(&mut v[..]).unwrap();
+/// assert_eq!(sorted.try_binary_search(&3.0), Ok(Ok(3)));
+/// assert_eq!(sorted.try_range(1.5..3.0), Ok(&[2.0, 2.0][..]));
+///
+/// let mut merged = sorted.try_merge(&Sorted::try_new(&[0.0, 2.0][..]).unwrap().unwrap()).unwrap();
+/// merged.dedup();
+/// assert_eq!(*merged, [0.0, 1.0, 2.0, 3.0]);
+///
+/// assert!(Sorted::try_new(&[2.0, 1.0][..]).unwrap().is_err());
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Sorted<S>(S);
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Slices and [`Vec`] which [`Sorted`] can be made from.
+///
+/// This is sealed, because the contents of other [`Deref`] types may change after the check.
+pub trait SortedStorage: Deref + sealed::Sealed {}
+
+impl<T> sealed::Sealed for &[T] {}
+impl<T> SortedStorage for &[T] {}
+impl<T> sealed::Sealed for &mut [T] {}
+impl<T> SortedStorage for &mut [T] {}
+#[cfg(feature = "std")]
+impl<T> sealed::Sealed for Vec<T> {}
+#[cfg(feature = "std")]
+impl<T> SortedStorage for Vec<T> {}
+
+impl<S> Sorted<S> {
+    /// Wraps `v`, which the caller has already made sorted.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) const fn new_unchecked(v: S) -> Self {
+        Sorted(v)
+    }
+
+    /// Returns the wrapped value mutably, for the caller which keeps it sorted.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn inner_mut(&mut self) -> &mut S {
+        &mut self.0
+    }
+
+    /// Returns the wrapped slice or [`Vec`].
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+
+impl<T, S> Sorted<S>
+where
+    S: SortedStorage + Deref<Target = [T]>,
+{
+    /// Checks that `v` is sorted, returning `Ok(Err(v))` if it is not.
+    #[inline]
+    pub fn try_new(v: S) -> OrderResult<Result<Self, S>>
+    where
+        T: PartialOrd<T>,
+    {
+        check_sorted_comparable(&v, OrderOperation::IsSorted)?;
+        Ok(
+            if v.try_is_sorted_by_partial_cmp(|a, b| a.partial_cmp(b))? {
+                Ok(Sorted(v))
+            } else {
+                Err(v)
+            },
+        )
+    }
+}
+
+impl<T, S> Sorted<S>
+where
+    S: Deref<Target = [T]>,
+{
+    /// Returns the sorted elements.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    /// Same as [`try_binary_search`](`TryBinarySearch::try_binary_search`), which is correct because the slice is sorted.
+    #[inline]
+    pub fn try_binary_search(&self, x: &T) -> OrderResult<Result<usize, usize>>
+    where
+        T: PartialOrd<T>,
+    {
+        self.0.try_binary_search(x)
+    }
+
+    /// Returns `true` if the slice has the element equal to `x`.
+    #[inline]
+    pub fn try_contains(&self, x: &T) -> OrderResult<bool>
+    where
+        T: PartialOrd<T>,
+    {
+        Ok(self.try_binary_search(x)?.is_ok())
+    }
+
+    /// Returns the elements in `range`, found by binary search.
+    pub fn try_range<R>(&self, range: R) -> OrderResult<&[T]>
+    where
+        T: PartialOrd<T>,
+        R: RangeBounds<T>,
+    {
+        let range = try_range_indices(&self.0, range, |a| a)?;
+        Ok(&self.0[range])
+    }
+
+    /// Merges with `other` into the new sorted [`Vec`] in linear time. Equal elements in `self` come first.
+    ///
+    /// On error, the indices are the positions in `self` followed by `other`.
+    #[cfg(feature = "std")]
+    pub fn try_merge<S2>(&self, other: &Sorted<S2>) -> OrderResult<Sorted<Vec<T>>>
+    where
+        T: PartialOrd<T> + Clone,
+        S2: Deref<Target = [T]>,
+    {
+        let (a, b) = (&*self.0, &*other.0);
+        let mut merged = Vec::with_capacity(a.len() + b.len());
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            let ord = b[j].partial_cmp(&a[i]).ok_or_else(|| {
+                InvalidOrderError::with_indices(OrderOperation::Sort, i, a.len() + j)
+            })?;
+            if ord == Ordering::Less {
+                merged.push(b[j].clone());
+                j += 1;
+            } else {
+                merged.push(a[i].clone());
+                i += 1;
+            }
+        }
+        merged.extend_from_slice(&a[i..]);
+        merged.extend_from_slice(&b[j..]);
+        Ok(Sorted(merged))
+    }
+
+    /// Copies the elements into the sorted [`Vec`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn to_vec(&self) -> Sorted<Vec<T>>
+    where
+        T: Clone,
+    {
+        Sorted(self.0.to_vec())
+    }
+}
+
+impl<T, S> Sorted<S>
+where
+    S: SortedStorage + DerefMut<Target = [T]>,
+{
+    /// Sorts `v` with [`try_sort`](`TrySort::try_sort`). `v` is dropped if it returns error.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn try_sort(v: S) -> OrderResult<Self>
+    where
+        T: PartialOrd<T>,
+    {
+        let mut v = v;
+        v.try_sort()?;
+        check_sorted_comparable(&v, OrderOperation::Sort)?;
+        Ok(Sorted(v))
+    }
+
+    /// Sorts `v` with [`try_sort_unstable`](`TrySort::try_sort_unstable`). `v` is dropped if it returns error.
+    #[inline]
+    pub fn try_sort_unstable(v: S) -> OrderResult<Self>
+    where
+        T: PartialOrd<T>,
+    {
+        let mut v = v;
+        v.try_sort_unstable()?;
+        check_sorted_comparable(&v, OrderOperation::Sort)?;
+        Ok(Sorted(v))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Sorted<Vec<T>> {
+    /// Removes the consecutive equal elements, which are all the duplicates because it is sorted.
+    #[inline]
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.0.dedup();
+    }
+}
+
+/// Checks the element of a slice with one element, which sorting, selecting or checking the order never compares.
+///
+/// Longer slices are already checked that way, because every element is compared with another.
+pub(crate) fn check_sorted_comparable<T: PartialOrd<T>>(
+    v: &[T],
+    operation: OrderOperation,
+) -> OrderResult<()> {
+    match v {
+        [x] => check_comparable(
+            x,
+            T::partial_cmp,
+            InvalidOrderError::with_index(operation, 0),
+        ),
+        _ => Ok(()),
+    }
+}
+
+impl<T> Default for Sorted<&[T]> {
+    #[inline]
+    fn default() -> Self {
+        Sorted(&[])
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Default for Sorted<Vec<T>> {
+    #[inline]
+    fn default() -> Self {
+        Sorted(Vec::new())
+    }
+}
+
+impl<S: Deref> Deref for Sorted<S> {
+    type Target = S::Target;
+
+    #[inline]
+    fn deref(&self) -> &S::Target {
+        &self.0
+    }
+}
+
+impl<T, S: Deref<Target = [T]>> AsRef<[T]> for Sorted<S> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use crate::*;
+    use rand::distributions::Standard;
+    use rand::prelude::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn sorted_range() {
+        let rng = thread_rng();
+        let v: Vec<f64> = Standard.sample_iter(rng).take(100).collect();
+        let sorted = Sorted::try_sort(v.clone()).unwrap();
+        let range = sorted.try_range(0.25..=0.75).unwrap();
+        let mut expected: Vec<_> = v
+            .into_iter()
+            .filter(|x| (0.25..=0.75).contains(x))
+            .collect();
+        expected.try_sort().unwrap();
+        assert_eq!(range, &expected[..]);
+        assert_eq!(sorted.try_range(0.5..0.5), Ok(&[][..]));
+        assert!(sorted.try_range(f64::NAN..).is_err());
+    }
+
+    #[test]
+    fn sorted_merge() {
+        let a = Sorted::try_new(vec![1.0, 3.0, 5.0]).unwrap().unwrap();
+        let b = Sorted::try_sort_unstable(vec![4.0, 2.0, 3.0]).unwrap();
+        let mut merged = a.try_merge(&b).unwrap();
+        assert_eq!(*merged, [1.0, 2.0, 3.0, 3.0, 4.0, 5.0]);
+        merged.dedup();
+        assert_eq!(merged.into_inner(), [1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert!(Sorted::try_new(vec![1.0, f64::NAN]).is_err());
+        let err = Sorted::try_new(vec![f64::NAN]).unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::IsSorted);
+        assert!(Sorted::try_sort(vec![f64::NAN]).is_err());
+        assert!(Sorted::try_sort_unstable(&mut [f64::NAN][..]).is_err());
+        assert!(Sorted::<Vec<f64>>::default().is_empty());
+    }
+}
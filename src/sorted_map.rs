@@ -0,0 +1,285 @@
+use crate::binary_search::try_range_indices;
+use crate::{
+    check_comparable, InvalidOrderError, OrderOperation, OrderResult, TryBinarySearch, TrySort,
+};
+use core::cmp::Ordering;
+use core::ops::RangeBounds;
+use std::vec::Vec;
+
+/// Map backed by the [`Vec`] of entries sorted by [`PartialOrd`] keys, for small tables like float thresholds.
+///
+/// Each new key is checked before it is stored, so a key like [`f64::NAN`] fails with [`InvalidOrderError`]
+/// even for an empty map, and the keys always stay in order.
+/// ```
+/// use try_partialord::*;
+///
+/// let tariff = TrySortedMap::try_from_vec(vec![(100.0, "high"), (0.0, "low"), (50.0, "mid")]).unwrap();
+/// assert_eq!(tariff.try_floor(&75.0), Ok(Some((&50.0, &"mid"))));
+/// assert_eq!(tariff.try_ceiling(&75.0), Ok(Some((&100.0, &"high"))));
+/// assert_eq!(tariff.try_floor(&-1.0), Ok(None));
+/// assert_eq!(tariff.try_get(&50.0), Ok(Some(&"mid")));
+/// assert_eq!(tariff.try_range(10.0..).map(|r| r.len()), Ok(2));
+/// assert!(tariff.try_floor(&f64::NAN).is_err());
+/// ```
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+pub struct TrySortedMap<K, V> {
+    entries: Vec<(K, V)>,
+}
+
+impl<K, V> TrySortedMap<K, V> {
+    /// Creates the empty map.
+    #[inline]
+    pub const fn new() -> Self {
+        TrySortedMap {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Creates the empty map with at least `capacity`.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        TrySortedMap {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Sorts the entries of `v` into the map. For the same keys, the last value is kept, the same as [`BTreeMap`](`std::collections::BTreeMap`).
+    pub fn try_from_vec(v: Vec<(K, V)>) -> OrderResult<Self>
+    where
+        K: PartialOrd<K>,
+    {
+        let mut entries = v;
+        entries.try_sort_by(|a, b| a.0.partial_cmp(&b.0).map(Ordering::is_lt))?;
+        if let [(k, _)] = &entries[..] {
+            check_comparable(
+                k,
+                K::partial_cmp,
+                InvalidOrderError::with_index(OrderOperation::Sort, 0),
+            )?;
+        }
+        // The sort is stable, so the later one of the same keys comes later.
+        // Keep the first key with the last value, the same as inserting them in order.
+        entries.dedup_by(|later, earlier| {
+            let same = later.0.partial_cmp(&earlier.0) == Some(Ordering::Equal);
+            if same {
+                core::mem::swap(&mut later.1, &mut earlier.1);
+            }
+            same
+        });
+        Ok(TrySortedMap { entries })
+    }
+
+    /// Collects and sorts the entries of `iter` into the map.
+    #[inline]
+    pub fn try_from_iter<I>(iter: I) -> OrderResult<Self>
+    where
+        K: PartialOrd<K>,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        Self::try_from_vec(iter.into_iter().collect())
+    }
+
+    /// Inserts the entry, returning the old value if there was `key`.
+    pub fn try_insert(&mut self, key: K, value: V) -> OrderResult<Option<V>>
+    where
+        K: PartialOrd<K>,
+    {
+        match self.search(&key)? {
+            Ok(i) => Ok(Some(core::mem::replace(&mut self.entries[i].1, value))),
+            Err(i) => {
+                self.entries.insert(i, (key, value));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Removes the entry of `key`, returning its value if there was.
+    pub fn try_remove(&mut self, key: &K) -> OrderResult<Option<V>>
+    where
+        K: PartialOrd<K>,
+    {
+        Ok(match self.search(key)? {
+            Ok(i) => Some(self.entries.remove(i).1),
+            Err(_) => None,
+        })
+    }
+
+    /// Returns the value of `key`.
+    #[inline]
+    pub fn try_get(&self, key: &K) -> OrderResult<Option<&V>>
+    where
+        K: PartialOrd<K>,
+    {
+        Ok(self.search(key)?.ok().map(|i| &self.entries[i].1))
+    }
+
+    /// Returns the value of `key` mutably.
+    #[inline]
+    pub fn try_get_mut(&mut self, key: &K) -> OrderResult<Option<&mut V>>
+    where
+        K: PartialOrd<K>,
+    {
+        Ok(match self.search(key)? {
+            Ok(i) => Some(&mut self.entries[i].1),
+            Err(_) => None,
+        })
+    }
+
+    /// Returns `true` if the map has `key`.
+    #[inline]
+    pub fn try_contains_key(&self, key: &K) -> OrderResult<bool>
+    where
+        K: PartialOrd<K>,
+    {
+        Ok(self.search(key)?.is_ok())
+    }
+
+    /// Returns the entry with the greatest key less than or equal to `key`.
+    pub fn try_floor(&self, key: &K) -> OrderResult<Option<(&K, &V)>>
+    where
+        K: PartialOrd<K>,
+    {
+        let i = self
+            .entries
+            .try_partition_point(|e| e.0.partial_cmp(key).map(Ordering::is_le))?;
+        Ok(i.checked_sub(1).map(|i| self.entry(i)))
+    }
+
+    /// Returns the entry with the least key greater than or equal to `key`.
+    pub fn try_ceiling(&self, key: &K) -> OrderResult<Option<(&K, &V)>>
+    where
+        K: PartialOrd<K>,
+    {
+        let i = self
+            .entries
+            .try_partition_point(|e| e.0.partial_cmp(key).map(Ordering::is_lt))?;
+        Ok(self.entries.get(i).map(|(k, v)| (k, v)))
+    }
+
+    /// Returns the entries whose keys are in `range`.
+    #[inline]
+    pub fn try_range<R>(&self, range: R) -> OrderResult<&[(K, V)]>
+    where
+        K: PartialOrd<K>,
+        R: RangeBounds<K>,
+    {
+        let range = try_range_indices(&self.entries, range, |e| &e.0)?;
+        Ok(&self.entries[range])
+    }
+
+    /// Returns the number of entries.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there is no entry.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the iterator of the entries in the order of the keys.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Returns the iterator of the keys in order.
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Returns the iterator of the values in the order of the keys.
+    #[inline]
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    /// Returns the entries sorted by the keys.
+    #[inline]
+    pub fn as_slice(&self) -> &[(K, V)] {
+        &self.entries
+    }
+
+    /// Returns the [`Vec`] of the entries sorted by the keys.
+    #[inline]
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.entries
+    }
+
+    fn entry(&self, i: usize) -> (&K, &V) {
+        let (k, v) = &self.entries[i];
+        (k, v)
+    }
+
+    /// Finds `key`, which is rejected even by an empty map if it cannot be compared with itself.
+    fn search(&self, key: &K) -> OrderResult<Result<usize, usize>>
+    where
+        K: PartialOrd<K>,
+    {
+        check_comparable(
+            key,
+            K::partial_cmp,
+            InvalidOrderError::new(OrderOperation::BinarySearch),
+        )?;
+        self.entries.try_binary_search_by(|e| e.0.partial_cmp(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::distributions::Standard;
+    use rand::prelude::*;
+    use std::collections::BTreeMap;
+    use std::vec::Vec;
+
+    #[test]
+    fn try_sorted_map_ok() {
+        let rng = thread_rng();
+        let keys: Vec<u8> = Standard.sample_iter(rng).take(100).collect();
+        let mut map = TrySortedMap::new();
+        let mut expected = BTreeMap::new();
+        for (i, k) in keys.iter().enumerate() {
+            let old = map.try_insert(*k as f32 / 4.0, i).unwrap();
+            assert_eq!(old, expected.insert(*k, i));
+        }
+        let entries: Vec<_> = expected
+            .iter()
+            .map(|(k, i)| (*k as f32 / 4.0, *i))
+            .collect();
+        assert_eq!(map.as_slice(), &entries[..]);
+        let from_vec =
+            TrySortedMap::try_from_iter(keys.iter().enumerate().map(|(i, k)| (*k as f32 / 4.0, i)))
+                .unwrap();
+        assert_eq!(from_vec, map);
+    }
+
+    #[test]
+    fn try_sorted_map_error() {
+        let mut map = TrySortedMap::try_from_vec(vec![(1.0, 'a'), (2.0, 'b'), (1.0, 'c')]).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.try_get(&1.0), Ok(Some(&'c')));
+        assert!(map.try_insert(f64::NAN, 'd').is_err());
+        assert!(map.try_get(&f64::NAN).is_err());
+        assert_eq!(map.try_remove(&1.0), Ok(Some('c')));
+        assert_eq!(map.try_ceiling(&1.5), Ok(Some((&2.0, &'b'))));
+        assert_eq!(map.try_ceiling(&2.5), Ok(None));
+        assert!(TrySortedMap::try_from_vec(vec![(f64::NAN, 'a')]).is_err());
+        let map = TrySortedMap::try_from_vec(vec![(0.0f64, 'a'), (-0.0, 'b')]).unwrap();
+        let mut inserted = TrySortedMap::new();
+        inserted.try_insert(0.0, 'a').unwrap();
+        inserted.try_insert(-0.0, 'b').unwrap();
+        assert_eq!(map.as_slice(), inserted.as_slice());
+        assert!(map.as_slice()[0].0.is_sign_positive());
+        assert!(inserted.as_slice()[0].0.is_sign_positive());
+        assert!(TrySortedMap::try_from_vec(vec![(f64::NAN, 'a'), (1.0, 'b')]).is_err());
+        let mut empty = TrySortedMap::<f64, char>::new();
+        assert!(empty.try_get(&f64::NAN).is_err());
+        assert!(empty.try_get_mut(&f64::NAN).is_err());
+        assert!(empty.try_contains_key(&f64::NAN).is_err());
+        assert!(empty.try_remove(&f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,303 @@
+use crate::{
+    check_comparable, InvalidOrderError, OrderOperation, OrderResult, Sorted, TryBinarySearch,
+};
+use core::cmp::Ordering;
+use core::ops::{Deref, RangeBounds};
+use std::vec::Vec;
+
+/// [`Vec`] which is always sorted, for [`PartialOrd`] elements.
+///
+/// Adding an element fails with [`InvalidOrderError`] if it cannot be compared with the elements or with itself,
+/// so [`f64::NAN`] is rejected even by an empty vector, and the order is never broken.
+/// Equal elements are kept in the order they are added.
+/// ```
+/// use try_partialord::*;
+///
+/// let mut v = TrySortedVec::try_from_iter(vec![3.0, 1.0]).unwrap();
+/// assert_eq!(v.try_insert(2.0), Ok(1));
+/// assert!(v.try_insert(f64::NAN).is_err());
+/// assert!(v.try_extend(vec![0.0, 4.0]).is_ok());
+/// assert_eq!(*v, [0.0, 1.0, 2.0, 3.0, 4.0]);
+///
+/// assert_eq!(v.try_remove(&1.0), Ok(Some(1.0)));
+/// assert_eq!(v.try_contains(&1.0), Ok(false));
+/// assert_eq!(v.try_range(2.0..), Ok(&[2.0, 3.0, 4.0][..]));
+/// ```
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+pub struct TrySortedVec<T> {
+    inner: Sorted<Vec<T>>,
+}
+
+impl<T> TrySortedVec<T> {
+    /// Creates the empty vector.
+    #[inline]
+    pub const fn new() -> Self {
+        TrySortedVec {
+            inner: Sorted::new_unchecked(Vec::new()),
+        }
+    }
+
+    /// Creates the empty vector with at least `capacity`.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        TrySortedVec {
+            inner: Sorted::new_unchecked(Vec::with_capacity(capacity)),
+        }
+    }
+
+    /// Sorts `v` into the vector. `v` is dropped if it returns error.
+    pub fn try_from_vec(v: Vec<T>) -> OrderResult<Self>
+    where
+        T: PartialOrd<T>,
+    {
+        Ok(TrySortedVec {
+            inner: Sorted::try_sort(v)?,
+        })
+    }
+
+    /// Collects and sorts the elements of `iter` into the vector.
+    #[inline]
+    pub fn try_from_iter<I>(iter: I) -> OrderResult<Self>
+    where
+        T: PartialOrd<T>,
+        I: IntoIterator<Item = T>,
+    {
+        Self::try_from_vec(iter.into_iter().collect())
+    }
+
+    /// Inserts `x` after the equal elements, returning the index where it is inserted.
+    pub fn try_insert(&mut self, x: T) -> OrderResult<usize>
+    where
+        T: PartialOrd<T>,
+    {
+        check_value(&x)?;
+        let inner = self.inner.inner_mut();
+        let found = inner.try_binary_search_by(|a| {
+            a.partial_cmp(&x).map(|o| {
+                if o == Ordering::Greater {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                }
+            })
+        })?;
+        let index = found.unwrap_or_else(|i| i);
+        inner.insert(index, x);
+        Ok(index)
+    }
+
+    /// Adds all elements of `iter`, sorting them first and merging them into the vector.
+    ///
+    /// The vector is left unchanged when it returns error.
+    /// On error while merging, the indices are the positions in the vector followed by the sorted new elements.
+    pub fn try_extend<I>(&mut self, iter: I) -> OrderResult<()>
+    where
+        T: PartialOrd<T>,
+        I: IntoIterator<Item = T>,
+    {
+        let added = Sorted::try_sort(iter.into_iter().collect::<Vec<T>>())?.into_inner();
+        let inner = self.inner.inner_mut();
+        // All comparisons are done before moving any element, so a failure leaves the vector as it is.
+        // `counts[j]` is the number of the elements of the vector before `added[j]`.
+        let mut counts = Vec::with_capacity(added.len());
+        let mut i = 0;
+        for (j, b) in added.iter().enumerate() {
+            while i < inner.len() {
+                let ord = b.partial_cmp(&inner[i]).ok_or_else(|| {
+                    InvalidOrderError::with_indices(OrderOperation::Sort, i, inner.len() + j)
+                })?;
+                if ord == Ordering::Less {
+                    break;
+                }
+                i += 1;
+            }
+            counts.push(i);
+        }
+        let mut old = core::mem::take(inner).into_iter();
+        inner.reserve(old.len() + added.len());
+        let mut taken = 0;
+        for (b, count) in added.into_iter().zip(counts) {
+            inner.extend(old.by_ref().take(count - taken));
+            inner.push(b);
+            taken = count;
+        }
+        inner.extend(old);
+        Ok(())
+    }
+
+    /// Removes one element equal to `x` and returns it, or [`None`] if there is not.
+    pub fn try_remove(&mut self, x: &T) -> OrderResult<Option<T>>
+    where
+        T: PartialOrd<T>,
+    {
+        check_value(x)?;
+        Ok(match self.inner.try_binary_search(x)? {
+            Ok(i) => Some(self.inner.inner_mut().remove(i)),
+            Err(_) => None,
+        })
+    }
+
+    /// Removes and returns the element at `index`.
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn remove_index(&mut self, index: usize) -> T {
+        self.inner.inner_mut().remove(index)
+    }
+
+    /// Removes and returns the largest element, or [`None`] if empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.inner.inner_mut().pop()
+    }
+
+    /// Removes all elements.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.inner.inner_mut().clear();
+    }
+
+    /// Returns `true` if the vector has the element equal to `x`.
+    #[inline]
+    pub fn try_contains(&self, x: &T) -> OrderResult<bool>
+    where
+        T: PartialOrd<T>,
+    {
+        check_value(x)?;
+        self.inner.try_contains(x)
+    }
+
+    /// Returns the elements in `range`.
+    #[inline]
+    pub fn try_range<R>(&self, range: R) -> OrderResult<&[T]>
+    where
+        T: PartialOrd<T>,
+        R: RangeBounds<T>,
+    {
+        self.inner.try_range(range)
+    }
+
+    /// Returns the elements as [`Sorted`] slice.
+    #[inline]
+    pub fn as_sorted(&self) -> Sorted<&[T]> {
+        Sorted::new_unchecked(&self.inner)
+    }
+
+    /// Returns the inner [`Sorted`] vector.
+    #[inline]
+    pub fn into_sorted(self) -> Sorted<Vec<T>> {
+        self.inner
+    }
+
+    /// Returns the sorted [`Vec`].
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.inner.into_inner()
+    }
+}
+
+impl<T> From<Sorted<Vec<T>>> for TrySortedVec<T> {
+    #[inline]
+    fn from(v: Sorted<Vec<T>>) -> Self {
+        TrySortedVec { inner: v }
+    }
+}
+
+impl<T> Deref for TrySortedVec<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        &self.inner
+    }
+}
+
+impl<T> AsRef<[T]> for TrySortedVec<T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        &self.inner
+    }
+}
+
+impl<T> IntoIterator for TrySortedVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_inner().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TrySortedVec<T> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.iter()
+    }
+}
+
+/// Checks that `x` can be compared with itself, so it is rejected even by an empty vector.
+fn check_value<T: PartialOrd<T>>(x: &T) -> OrderResult<()> {
+    check_comparable(
+        x,
+        T::partial_cmp,
+        InvalidOrderError::new(OrderOperation::BinarySearch),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use rand::distributions::Standard;
+    use rand::prelude::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn try_sorted_vec_ok() {
+        let rng = thread_rng();
+        let v: Vec<f32> = Standard.sample_iter(rng).take(100).collect();
+        let mut sorted_vec = TrySortedVec::new();
+        for x in &v[..50] {
+            sorted_vec.try_insert(*x).unwrap();
+        }
+        sorted_vec.try_extend(v[50..].iter().copied()).unwrap();
+        let mut expected = v.clone();
+        expected.try_sort().unwrap();
+        assert_eq!(*sorted_vec, expected[..]);
+        for x in &v {
+            assert_eq!(sorted_vec.try_remove(x), Ok(Some(*x)));
+        }
+        assert!(sorted_vec.is_empty());
+    }
+
+    #[test]
+    fn try_sorted_vec_error() {
+        let mut v = TrySortedVec::try_from_iter(vec![(1.0, 'a'), (0.0, 'b')]).unwrap();
+        assert_eq!(v.try_insert((1.0, 'c')), Ok(2));
+        let err = v.try_insert((f64::NAN, 'd')).unwrap_err();
+        assert_eq!(err.operation(), OrderOperation::BinarySearch);
+        assert_eq!(err.index(), None);
+        assert!(v.try_extend(vec![(2.0, 'e'), (f64::NAN, 'f')]).is_err());
+        assert!(v.try_extend(vec![(f64::NAN, 'f')]).is_err());
+        assert_eq!(v.len(), 3);
+        assert!(TrySortedVec::try_from_vec(vec![f64::NAN]).is_err());
+        assert!(v.try_contains(&(f64::NAN, 'a')).is_err());
+
+        let mut empty = TrySortedVec::<f64>::new();
+        assert!(empty.try_contains(&f64::NAN).is_err());
+        assert!(empty.try_remove(&f64::NAN).is_err());
+        let mut v = TrySortedVec::try_from_iter(vec![(1.0, 'a'), (3.0, 'b')]).unwrap();
+        v.try_extend(vec![(3.0, 'a'), (0.0, 'c'), (1.0, 'a')])
+            .unwrap();
+        let expected = [(0.0, 'c'), (1.0, 'a'), (1.0, 'a'), (3.0, 'a'), (3.0, 'b')];
+        assert_eq!(*v, expected);
+        v.try_extend(Vec::new()).unwrap();
+        assert_eq!(*v, expected);
+        let mut zeros = TrySortedVec::try_from_vec(vec![0.0f64]).unwrap();
+        zeros.try_extend(vec![-0.0]).unwrap();
+        assert!(zeros[0].is_sign_positive() && zeros[1].is_sign_negative());
+    }
+}